@@ -3,12 +3,25 @@
 //! Arguments follow util-linux cal convention: `[[day] month] year`
 
 use chrono::Datelike;
-use clap::{Parser, ValueHint};
+use clap::{CommandFactory, FromArgMatches, Parser, ValueHint};
+use std::ffi::OsString;
 use std::io::IsTerminal;
 
+use crate::astro::Location;
+use crate::calendar::days_in_month_for;
+use crate::clock::Clock;
+use crate::colors::{Palette, TerminalColors};
+use crate::config;
+use crate::error::{CalError, ErrorKind, closest};
+use crate::formatter::{get_month_name, locale_week, locale_week_defaults, suggest_month};
+use crate::ncal;
+use crate::project::LazyProject;
+use crate::query::Query;
 use crate::types::{
-    COLOR_ENABLED_BY_DEFAULT, CalContext, ColumnsMode, GUTTER_WIDTH_REGULAR, ReformType, WeekType,
+    COLOR_ENABLED_BY_DEFAULT, CalContext, ColorWhen, ColumnsMode, Compat, DisplayDate, Easter,
+    GUTTER_WIDTH_REGULAR, Period, ReformType, Theme, WeekHighlight, WeekType,
 };
+use crate::zoneinfo::TimeZone;
 
 #[derive(Parser, Debug)]
 #[command(name = "cal")]
@@ -16,11 +29,11 @@ use crate::types::{
 #[command(version)]
 #[command(after_help = HELP_MESSAGE)]
 pub struct Args {
-    /// Week starts on Sunday (default is Monday).
+    /// Week starts on Sunday (default from the locale, Monday in C/POSIX).
     #[arg(short = 's', long, help_heading = "Calendar options")]
     pub sunday: bool,
 
-    /// Week starts on Monday (default).
+    /// Week starts on Monday.
     #[arg(short = 'm', long, help_heading = "Calendar options")]
     pub monday: bool,
 
@@ -28,18 +41,22 @@ pub struct Args {
     #[arg(short = 'j', long, help_heading = "Calendar options")]
     pub julian: bool,
 
-    /// Display week numbers.
-    #[arg(short = 'w', long, help_heading = "Calendar options")]
-    pub week_numbers: bool,
-
-    /// Week numbering system (iso or us).
+    /// Display week numbers; highlight week NUM of the year (default: the current week).
     #[arg(
+        short = 'w',
         long,
-        default_value = "iso",
         help_heading = "Calendar options",
-        value_name = "system"
+        value_name = "num",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "current",
+        value_parser = parse_week_highlight
     )]
-    pub week_type: WeekType,
+    pub week_numbers: Option<WeekHighlight>,
+
+    /// Week numbering system (iso or us; default from the locale).
+    #[arg(long, help_heading = "Calendar options", value_name = "system")]
+    pub week_type: Option<WeekType>,
 
     /// Display whole year.
     #[arg(short = 'y', long, help_heading = "Display options")]
@@ -62,6 +79,14 @@ pub struct Args {
     )]
     pub months_count: Option<u32>,
 
+    /// Display a single week as a strip (current week, or week N of the year argument).
+    #[arg(long, help_heading = "Display options", value_name = "num")]
+    pub week: Option<Option<u32>>,
+
+    /// Display the quarter of the current or given month, titled "Q3 2026".
+    #[arg(long, help_heading = "Display options")]
+    pub quarter: bool,
+
     /// Show only a single month (default).
     #[arg(short = '1', long = "one", help_heading = "Display options")]
     pub one_month: bool,
@@ -83,6 +108,11 @@ pub struct Args {
     #[arg(long, help_heading = "Calendar options")]
     pub iso: bool,
 
+    /// Take the date as today instead of the system date (also CAL_TEST_TIME).
+    #[arg(long, help_heading = "Calendar options", value_name = "YYYY-MM-DD",
+          value_parser = parse_today)]
+    pub today: Option<chrono::NaiveDate>,
+
     /// Day (1-31) - optional, used with month and year.
     #[arg(index = 1, default_value = None, value_name = "day", value_hint = ValueHint::Other)]
     pub day_arg: Option<String>,
@@ -95,9 +125,21 @@ pub struct Args {
     #[arg(index = 3, default_value = None, value_name = "year", value_hint = ValueHint::Other)]
     pub year_arg: Option<String>,
 
-    /// Disable colorized output.
-    #[arg(long, help_heading = "Output options")]
-    pub color: bool,
+    /// Colorize output: auto, always or never.
+    ///
+    /// A bare --color (without a value) is deprecated and disables color as before.
+    #[arg(
+        long,
+        help_heading = "Output options",
+        value_name = "when",
+        num_args = 0..=1,
+        require_equals = true
+    )]
+    pub color: Option<Option<ColorWhen>>,
+
+    /// Color theme (auto asks the terminal for its background).
+    #[arg(long, help_heading = "Output options", value_name = "name")]
+    pub theme: Option<Theme>,
 
     /// Number of columns for multiple months (or "auto" for terminal width).
     #[arg(
@@ -112,6 +154,75 @@ pub struct Args {
     #[arg(short = 'v', long, help_heading = "Output options")]
     pub vertical: bool,
 
+    /// BSD ncal options and vertical layout (also when run as `ncal`).
+    #[arg(long, help_heading = "Output options")]
+    pub ncal: bool,
+
+    /// Layout compatibility: util-linux reproduces its output byte for byte.
+    ///
+    /// Defaults to util-linux when POSIXLY_CORRECT is set and the program runs as `cal`.
+    #[arg(long, help_heading = "Output options", value_name = "mode")]
+    pub compat: Option<Compat>,
+
+    /// Mark equinoxes and solstices and list their exact time.
+    #[arg(long, help_heading = "Output options")]
+    pub seasons: bool,
+
+    /// List sunrise, sunset and day length for a location (LAT,LON or city name).
+    #[arg(long, help_heading = "Output options", value_name = "place")]
+    pub location: Option<String>,
+
+    /// Mark polar days and polar nights at the location.
+    #[arg(long, help_heading = "Output options", requires = "location")]
+    pub polar: bool,
+
+    /// Time zone from the system zoneinfo (e.g. Europe/Berlin) for today and event times.
+    ///
+    /// May be repeated: today in the other zones is marked where the date differs.
+    #[arg(long = "tz", help_heading = "Output options", value_name = "zone")]
+    pub time_zones: Vec<String>,
+
+    /// Mark days on which the UTC offset changes (daylight saving time).
+    #[arg(long, help_heading = "Output options")]
+    pub dst: bool,
+
+    /// Find dates matching a query ("fri 13", "last mon of may", "months starting on sun").
+    ///
+    /// With a year range argument (e.g. 2026..2035) the matches are listed,
+    /// otherwise they are highlighted in the displayed calendar.
+    #[arg(long, help_heading = "Search options", value_name = "query")]
+    pub find: Option<String>,
+
+    /// List years whose calendar is identical to the given year.
+    ///
+    /// Searches the year range argument (e.g. 1900..2100), by default 100 years either side.
+    #[arg(long, help_heading = "Search options", value_name = "year",
+          value_parser = clap::value_parser!(i32).range(1..=9999))]
+    pub same_as: Option<i32>,
+
+    /// Print facts about the date, month or year given as arguments (default: today).
+    #[arg(long, help_heading = "Search options")]
+    pub info: bool,
+
+    /// Print the date of Easter in the year argument (default: current year).
+    #[arg(
+        long,
+        help_heading = "Search options",
+        value_name = "church",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "western"
+    )]
+    pub easter: Option<Easter>,
+
+    /// List the Gregorian reform dates of countries; * marks the --reform in use.
+    #[arg(long, help_heading = "Search options")]
+    pub reforms: bool,
+
+    /// With --same-as, also list years matching only in Jan, Jan-Feb or Mar-Dec.
+    #[arg(long, help_heading = "Search options", requires = "same_as")]
+    pub partial: bool,
+
     /// Highlight holidays using isdayoff.ru API (requires plugin).
     ///
     /// **Note:** Build the workspace to include the plugin:
@@ -124,6 +235,23 @@ pub struct Args {
     /// - `/usr/lib/cal/plugins/`
     #[arg(short = 'H', long = "holidays", help_heading = "Output options")]
     pub holidays: bool,
+
+    /// Country code for holidays (default from the locale, e.g. RU).
+    #[cfg(feature = "plugins")]
+    #[arg(long, help_heading = "Output options", value_name = "code")]
+    pub country: Option<String>,
+
+    /// Use a named profile from the config file (also CAL_PROFILE).
+    #[arg(long, help_heading = "Config options", value_name = "name")]
+    pub profile: Option<String>,
+
+    /// Print the effective settings and where they come from, then exit.
+    #[arg(long, help_heading = "Config options")]
+    pub print_config: bool,
+
+    /// Ignore the .calendar.toml project calendar.
+    #[arg(long, help_heading = "Config options")]
+    pub no_project: bool,
 }
 
 /// Help message displayed with --help.
@@ -138,21 +266,85 @@ Examples:
   cal -Y             Display next twelve months
   cal 2 2026         Display February 2026
   cal 2026           Display year 2026
+  cal 0012           Display year 12 (one or two digits mean a month)
   cal --span -n 12   Display 12 months centered on current month
-  cal --color        Disable colorized output
+  cal -w=10 2026     Week numbers, highlighting week 10 (plain -w: current week)
+  cal -y --seasons   Mark equinoxes and solstices of the year
+  cal --location moscow  List sunrise and sunset for each day
+  cal -y --dst --tz Europe/Berlin --tz America/New_York
+                     Mark daylight saving time changes in both zones
+  cal --tz America/Los_Angeles --tz Australia/Sydney
+                     Mark today in both zones when the dates differ
+  cal --find 'fri 13' 2026..2035
+                     List every Friday the 13th in 2026-2035
+  cal -y --find 'last mon of may'
+                     Highlight the last Monday of May in the year
+  cal --same-as 2026 List years with the same calendar as 2026
+  cal --same-as 2026 --partial 2000..2050
+                     Include years matching only in Jan, Jan-Feb or Mar-Dec
+  cal --info 2026    Leap year, ISO weeks, Easter and more for 2026
+  cal --info 18 2 2026
+                     Day of year, ISO week date, Julian Day Number, ...
+  cal 2026-03-15     ISO date (also 15.03.2026, or 03/15/2026 in en_US)
+  cal next year      Relative forms: next, last, this, +N, -- -N
+  cal 2026-03..2026-08
+                     Months March to August 2026 (2025..2027 for whole years)
+  cal Q3 2026        Third quarter of 2026 (H1, H2 for halves; --quarter for current)
+  cal --week         Current week with full weekday names
+  cal --week 8 2026  Week 8 of 2026 (numbering from --week-type)
+  cal --theme solarized-light
+                     Colors for light terminals (mono, high-contrast, colorblind)
+  cal --profile work Use a profile from ~/.config/cal/config.toml
+  cal --print-config Show effective settings and their source
+  cal --no-project   Ignore .calendar.toml found in this or a parent directory
+  ncal -w            BSD ncal: vertical months, week numbers below (also --ncal)
+  ncal -e 2026       Date of Easter 2026 (-o for Orthodox Easter)
+  cal --reforms      Gregorian reform dates of countries (ncal -p)
+  cal --compat=util-linux -y
+                     Year in the exact util-linux layout (for scripts)
+  cal --today 2026-02-18
+                     Take 18 February 2026 as today (for scripts and tests)
+  cal --color=always | less -R
+                     Keep colors through a pager (--color=never disables them)
   cal -H             Highlight holidays (requires plugin, see --help)";
 
 impl Args {
+    /// Parse the command line over the config file settings; handles --print-config.
     pub fn parse() -> Self {
-        Parser::parse()
+        let cmd = Args::command();
+        let fail = |kind: ErrorKind, message: String| -> ! {
+            let e = CalError::new(kind, message);
+            eprintln!("cal: {}", e);
+            std::process::exit(e.exit_code());
+        };
+        let argv = attach_short_values(std::env::args_os().collect(), &cmd);
+        let argv = ncal::translate(argv, &cmd).unwrap_or_else(|e| fail(ErrorKind::Argument, e));
+        let (cmd, config) =
+            config::merge(&argv, cmd).unwrap_or_else(|e| fail(ErrorKind::Config, e));
+        let matches = cmd.clone().get_matches_from(argv);
+        let args = Args::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+        if args.print_config {
+            // Nothing to report if the reader has gone away
+            let _ = config::print_config(&mut std::io::stdout().lock(), &config, &cmd, &matches);
+            std::process::exit(0);
+        }
+        args
     }
 }
 
 impl CalContext {
     pub fn new(args: &Args) -> Result<Self, String> {
-        let today = get_today_date();
+        Self::build(args, None)
+    }
 
-        let color = !args.color && COLOR_ENABLED_BY_DEFAULT && std::io::stdout().is_terminal();
+    /// Build the context with "today" taken from the given clock.
+    pub fn with_clock(args: &Args, clock: Clock) -> Result<Self, String> {
+        Self::build(args, Some(clock))
+    }
+
+    fn build(args: &Args, clock: Option<Clock>) -> Result<Self, String> {
+        let terminal_colors = TerminalColors::load(std::env::var("TERM").ok().as_deref());
+        let color = color_enabled(args.color, terminal_colors.enabled);
 
         let columns = match args.columns.as_deref() {
             Some("auto") | None => ColumnsMode::Auto,
@@ -177,15 +369,44 @@ impl CalContext {
             return Err("Options -y, -Y, and -n are mutually exclusive".to_string());
         }
 
-        if let Some(year_str) = &args.year_arg {
-            let year: i32 = year_str
-                .parse()
-                .map_err(|_| format!("Invalid year value: {}", year_str))?;
-            if !(1..=9999).contains(&year) {
-                return Err(format!("Invalid year value: {} (must be 1-9999)", year));
-            }
+        let location = args.location.as_deref().map(Location::parse).transpose()?;
+        let find = args.find.as_deref().map(Query::parse).transpose()?;
+
+        let mut time_zones = args
+            .time_zones
+            .iter()
+            .map(|name| TimeZone::load(name))
+            .collect::<Result<Vec<_>, _>>()?;
+        if args.dst && time_zones.is_empty() {
+            time_zones.push(TimeZone::local());
         }
 
+        // Today in the first zone; other zones are marked where their date differs
+        let reference_zone = args.time_zones.first().map(|_| &time_zones[0]);
+        let clock = clock.unwrap_or_else(|| clock_for(args, reference_zone));
+        let today = clock.today(reference_zone);
+        let zone_todays = time_zones
+            .iter()
+            .skip(1)
+            .map(|zone| (zone.name.clone(), clock.today(Some(zone))))
+            .filter(|(_, date)| *date != today)
+            .collect();
+
+        // --iso overrides --reform
+        let reform_year = reform_year(args);
+
+        // Day argument in "cal 15 9 2026"; other argument forms carry no day
+        let date = parse_date_at(args, today, reform_year);
+        let selected = match &date {
+            Ok(DisplayDate {
+                year,
+                month,
+                day: Some(day),
+                ..
+            }) => Some((*year, *month, *day)),
+            _ => None,
+        };
+
         // Vertical mode uses narrower gutter for compact layout
         let gutter_width = if args.vertical {
             1
@@ -193,121 +414,686 @@ impl CalContext {
             GUTTER_WIDTH_REGULAR
         };
 
-        // --iso overrides --reform
-        let reform_year = if args.iso {
-            ReformType::Iso.reform_year()
+        // util-linux starts weeks on Sunday in the C locale
+        let compat = compat_enabled(args.compat);
+        let (locale_start, locale_week_type) = match locale_week() {
+            Some(defaults) => defaults,
+            None if compat => (chrono::Weekday::Sun, WeekType::Us),
+            None => locale_week_defaults(),
+        };
+        let week_start = if args.sunday {
+            chrono::Weekday::Sun
+        } else if args.monday {
+            chrono::Weekday::Mon
         } else {
-            args.reform.reform_year()
+            locale_start
         };
+        // US numbering only goes with Sunday-first weeks
+        let week_type = args
+            .week_type
+            .unwrap_or(if week_start == chrono::Weekday::Sun {
+                locale_week_type
+            } else {
+                WeekType::Iso
+            });
 
-        Ok(CalContext {
+        let mut ctx = CalContext {
             reform_year,
-            week_start: if args.sunday {
-                chrono::Weekday::Sun
-            } else {
-                chrono::Weekday::Mon
-            },
+            week_start,
             julian: args.julian,
-            week_numbers: args.week_numbers,
-            week_type: args.week_type,
+            week_numbers: args.week_numbers.is_some(),
+            highlight_week: None,
+            week_type,
             color,
+            palette: if color {
+                Palette::load(args.theme, terminal_colors.scheme.as_deref())
+            } else {
+                Palette::default()
+            },
             vertical: args.vertical,
+            compat,
             today,
+            clock,
+            date,
+            selected,
             show_year_in_header: true,
             gutter_width,
             columns,
             span: args.span,
+            seasons: args.seasons,
+            location,
+            polar: args.polar,
+            time_zones,
+            dst: args.dst,
+            zone_todays,
+            find,
+            project: if args.no_project {
+                LazyProject::default()
+            } else {
+                LazyProject::discover()
+            },
             #[cfg(feature = "plugins")]
             holidays: args.holidays,
+            #[cfg(feature = "plugins")]
+            country: args.country.as_deref().map(str::to_uppercase),
+        };
+
+        // -w highlights the current week, -w=N week N of the requested year
+        ctx.highlight_week = match args.week_numbers {
+            Some(WeekHighlight::Number(week)) => {
+                let year = ctx.date.as_ref().map_or(today.year(), |date| date.year);
+                Some((year, week))
+            }
+            Some(WeekHighlight::Current) => Some(ctx.week_of(today)),
+            None => None,
+        };
+        Ok(ctx)
+    }
+}
+
+/// Decide whether to use the util-linux layout: --compat, else POSIXLY_CORRECT when run as `cal`.
+fn compat_enabled(compat: Option<Compat>) -> bool {
+    match compat {
+        Some(compat) => compat == Compat::UtilLinux,
+        None => {
+            std::env::var_os("POSIXLY_CORRECT").is_some()
+                && std::env::args_os()
+                    .next()
+                    .and_then(|argv0| {
+                        std::path::Path::new(&argv0)
+                            .file_name()
+                            .map(|name| name == "cal")
+                    })
+                    .unwrap_or(false)
+        }
+    }
+}
+
+/// Decide whether to colorize output.
+///
+/// In auto mode NO_COLOR disables color, CLICOLOR_FORCE enables it, and TERM=dumb or a
+/// non-terminal stdout disables it; otherwise a terminal-colors.d enable or disable
+/// file (`files`) overrides the built-in default.
+fn color_enabled(when: Option<Option<ColorWhen>>, files: Option<bool>) -> bool {
+    let non_empty = |var: &str| std::env::var(var).is_ok_and(|value| !value.is_empty());
+    match when {
+        // Deprecated util-linux 2.x spelling, see deprecation_warning
+        Some(None) => false,
+        Some(Some(ColorWhen::Always)) => true,
+        Some(Some(ColorWhen::Never)) => false,
+        Some(Some(ColorWhen::Auto)) | None => {
+            if non_empty("NO_COLOR") {
+                false
+            } else if non_empty("CLICOLOR_FORCE")
+                && std::env::var("CLICOLOR_FORCE").is_ok_and(|value| value != "0")
+            {
+                true
+            } else if std::env::var("TERM").is_ok_and(|term| term == "dumb") {
+                false
+            } else {
+                files.unwrap_or(COLOR_ENABLED_BY_DEFAULT) && std::io::stdout().is_terminal()
+            }
+        }
+    }
+}
+
+/// Warning about a deprecated option form, for the caller to print.
+pub fn deprecation_warning(args: &Args) -> Option<&'static str> {
+    (args.color == Some(None)).then_some("--color without a value is deprecated, use --color=never")
+}
+
+/// Accept util-linux's attached optional values: `-w10` becomes `-w=10`.
+///
+/// clap only reads an optional value after '=', and would otherwise take the
+/// digits for more flags of the cluster.
+pub fn attach_short_values(argv: Vec<OsString>, cmd: &clap::Command) -> Vec<OsString> {
+    let optional = |flag: char| {
+        cmd.get_arguments().any(|arg| {
+            arg.get_short() == Some(flag)
+                && arg.is_require_equals_set()
+                && arg.get_num_args().is_some_and(|n| n.min_values() == 0)
         })
+    };
+    let mut options = true;
+    argv.into_iter()
+        .map(|arg| {
+            let text = arg.to_string_lossy();
+            if text == "--" {
+                options = false;
+            }
+            if !options || text.starts_with("--") || !text.starts_with('-') {
+                return arg;
+            }
+            let cluster: Vec<char> = text.chars().skip(1).collect();
+            match cluster
+                .windows(2)
+                .position(|pair| optional(pair[0]) && pair[1].is_ascii_digit())
+            {
+                Some(at) => {
+                    let (flags, value): (String, String) = (
+                        cluster[..=at].iter().collect(),
+                        cluster[at + 1..].iter().collect(),
+                    );
+                    format!("-{}={}", flags, value).into()
+                }
+                None => arg,
+            }
+        })
+        .collect()
+}
+
+/// Parse the -w week: a week number or "current".
+fn parse_week_highlight(s: &str) -> Result<WeekHighlight, String> {
+    match s.parse::<u32>() {
+        _ if s == "current" => Ok(WeekHighlight::Current),
+        Ok(week @ 1..=53) => Ok(WeekHighlight::Number(week)),
+        _ => Err(format!("Invalid week number: {} (expected 1-53)", s)),
     }
 }
 
-/// Get today's date, respecting CAL_TEST_TIME environment variable for testing.
-pub fn get_today_date() -> chrono::NaiveDate {
-    if let Ok(test_time) = std::env::var("CAL_TEST_TIME")
-        && let Ok(date) = chrono::NaiveDate::parse_from_str(&test_time, "%Y-%m-%d")
-    {
-        return date;
+/// Parse the --today date.
+fn parse_today(s: &str) -> Result<chrono::NaiveDate, String> {
+    chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date: {} (expected YYYY-MM-DD)", s))
+}
+
+/// Clock for the arguments: --today, else CAL_TEST_TIME, else the system clock.
+///
+/// A date is taken in the zone "today" is computed in (the first `--tz` zone).
+pub fn clock_for(args: &Args, zone: Option<&TimeZone>) -> Clock {
+    match args.today {
+        Some(date) => Clock::at_date(date, zone),
+        None => Clock::from_env(zone),
+    }
+}
+
+/// Parse an inclusive year range such as "2026..2035".
+///
+/// Returns `Ok(None)` when the text is not a range.
+pub fn parse_year_range(s: &str) -> Result<Option<(i32, i32)>, CalError> {
+    parse_range(s, |text, _| range_year(text))
+}
+
+/// Split an inclusive range "first..last" and parse its ends; the flag marks the last end.
+///
+/// Returns `Ok(None)` when the text is not a range.
+fn parse_range<T: PartialOrd>(
+    s: &str,
+    parse_end: impl Fn(&str, bool) -> Result<T, CalError>,
+) -> Result<Option<(T, T)>, CalError> {
+    let Some((first, last)) = s.split_once("..") else {
+        return Ok(None);
+    };
+    let (first, last) = (parse_end(first, false)?, parse_end(last, true)?);
+    if first > last {
+        return Err(CalError::new(
+            ErrorKind::Argument,
+            format!("Invalid range: {} (start is after end)", s),
+        ));
     }
-    chrono::Local::now().date_naive()
+    Ok(Some((first, last)))
+}
+
+/// Year at one end of a range.
+fn range_year(text: &str) -> Result<i32, CalError> {
+    text.parse::<i32>()
+        .ok()
+        .filter(|year| (1..=9999).contains(year))
+        .ok_or_else(|| {
+            CalError::new(
+                ErrorKind::Year,
+                format!("Invalid year in range: {} (must be 1-9999)", text),
+            )
+        })
 }
 
 /// Calculate display date from positional arguments.
 ///
 /// Argument patterns:
-/// - 1 arg: year (4 digits) or month (1-2 digits)
-/// - 2 args: month year
+/// - 1 arg: year (4 digits), month (1-2 digits or name), ISO date (2026-03, 2026-03-15),
+///   dotted date (15.03.2026), slashed date (locale order), relative (+2, -1, next, last),
+///   range of months or years (2026-03..2026-08, 2025..2027)
+/// - 2 args: month year, quarter or half year (Q3 2026, H1 2026),
+///   or relative with unit (next year, +2 months)
 /// - 3 args: day month year
-pub fn get_display_date(args: &Args) -> Result<(i32, u32, Option<u32>), String> {
-    let today = get_today_date();
+pub fn get_display_date(
+    args: &Args,
+    ctx: &CalContext,
+) -> Result<(i32, u32, Option<u32>), CalError> {
+    parse_display_date(args, ctx).map(|date| (date.year, date.month, date.day))
+}
+
+/// Parse positional arguments into a display date, relative to the context's today.
+pub fn parse_display_date(args: &Args, ctx: &CalContext) -> Result<DisplayDate, CalError> {
+    parse_date_at(args, ctx.today, ctx.reform_year)
+}
 
+/// Parse positional arguments relative to today, rejecting days the month lacks.
+fn parse_date_at(
+    args: &Args,
+    today: chrono::NaiveDate,
+    reform_year: i32,
+) -> Result<DisplayDate, CalError> {
+    let date = parse_positional(args, today)?;
+    if let Some(day) = date.day {
+        let length = days_in_month_for(date.year, date.month, reform_year);
+        if day > length {
+            return Err(CalError::new(
+                ErrorKind::Day,
+                format!(
+                    "Invalid day: {} ({} {} has {} days)",
+                    day,
+                    get_month_name(date.month),
+                    date.year,
+                    length
+                ),
+            ));
+        }
+    }
+    Ok(date)
+}
+
+/// Note for a lone zero-padded number such as "012", which is read as a month but may mean a year.
+pub fn lone_number_note(args: &Args) -> Option<String> {
+    let val = args.day_arg.as_deref()?;
+    // One or two digits are plainly a month, four digits a year
+    if args.month_arg.is_some()
+        || matches!(val.len(), 1 | 2 | 4)
+        || !val.bytes().all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    let num: u32 = val.parse().ok().filter(|num| (1..=12).contains(num))?;
+    Some(format!(
+        "{} is taken as a month; use `cal {:04}` for the year {}",
+        val, num, num
+    ))
+}
+
+/// Parse positional arguments without checking the day against the month length.
+fn parse_positional(args: &Args, today: chrono::NaiveDate) -> Result<DisplayDate, CalError> {
     let day_provided = args.day_arg.is_some();
     let month_provided = args.month_arg.is_some();
     let year_provided = args.year_arg.is_some();
 
+    // Relative forms: "next", "last year", "+2", "-1 years"
+    if let Some(val) = &args.day_arg
+        && !year_provided
+        && let Some(date) = parse_relative(val, args.month_arg.as_deref(), today)?
+    {
+        return Ok(date);
+    }
+
+    // Quarter or half: "Q3", "H1 2026"
+    if let Some(val) = &args.day_arg
+        && !year_provided
+        && let Some(period) = parse_period(val)
+    {
+        let year = match &args.month_arg {
+            Some(year_str) => parse_year(year_str)?,
+            None => today.year(),
+        };
+        return Ok(DisplayDate::period(year, period));
+    }
+
     match (day_provided, month_provided, year_provided) {
-        // One argument: could be year (4 digits) or month (1-2 digits)
+        // One argument: year, month, or a date in ISO, dotted or slashed form
         (true, false, false) => {
             let val = args.day_arg.as_ref().unwrap();
             if let Ok(num) = val.parse::<i32>() {
-                // 4 digits = year
-                if (1000..=9999).contains(&num) {
-                    return Ok((num, today.month(), None));
+                // 4 digits = year ("0012" is the year 12)
+                if val.len() == 4 || num > 9999 {
+                    return parse_year(val).map(|year| DisplayDate::year(year, today.month()));
                 }
                 // 1-2 digits = month
                 if (1..=12).contains(&num) {
-                    return Ok((today.year(), num as u32, None));
+                    return Ok(DisplayDate::month(today.year(), num as u32));
+                }
+                if num == 0 {
+                    return Err(CalError::new(
+                        ErrorKind::Month,
+                        "Invalid month: 0 (must be 1-12)",
+                    ));
                 }
+                // 13..999: not a month, and years need four digits
+                return Err(CalError::new(
+                    ErrorKind::Ambiguous,
+                    format!(
+                        "Ambiguous argument: {} (neither a month 1-12 nor a four-digit year)",
+                        val
+                    ),
+                )
+                .with_hint(Some(format!(
+                    "for the year {} use `cal {:04}`; one or two digits mean a month (`cal 12` is December)",
+                    num, num
+                ))));
             }
             // Try parsing as month name
             if let Some(month) = crate::formatter::parse_month(val) {
-                return Ok((today.year(), month, None));
+                return Ok(DisplayDate::month(today.year(), month));
             }
-            Err(format!("Invalid argument: {}", val))
+            if let Some(date) = parse_month_range(val)? {
+                return Ok(date);
+            }
+            if let Some(date) = parse_date_string(val)? {
+                return Ok(date);
+            }
+            if let Some(month) = suggest_month(val) {
+                return Err(
+                    CalError::new(ErrorKind::Month, format!("Invalid month: {}", val))
+                        .with_hint(Some(format!("did you mean {}?", month))),
+                );
+            }
+            Err(
+                CalError::new(ErrorKind::Argument, format!("Invalid argument: {}", val))
+                    .with_hint(did_you_mean(val, RELATIVE_WORDS)),
+            )
         }
         // Two arguments: month year (e.g., cal 2 2026)
         (true, true, false) => {
-            let month = crate::formatter::parse_month(args.day_arg.as_ref().unwrap())
-                .ok_or_else(|| format!("Invalid month: {}", args.day_arg.as_ref().unwrap()))?;
-            let year = args
-                .month_arg
-                .as_ref()
-                .unwrap()
-                .parse::<i32>()
-                .map_err(|_| format!("Invalid year: {}", args.month_arg.as_ref().unwrap()))?;
-            if !(1..=9999).contains(&year) {
-                return Err(format!("Invalid year: {} (must be 1-9999)", year));
+            let (first, second) = (
+                args.day_arg.as_ref().unwrap(),
+                args.month_arg.as_ref().unwrap(),
+            );
+            // Year before month: "cal 2026 3"
+            if first.parse::<i32>().is_ok_and(|num| num > 12)
+                && let Some(month) = crate::formatter::parse_month(second)
+            {
+                return Err(CalError::new(
+                    ErrorKind::Ambiguous,
+                    format!(
+                        "Ambiguous arguments: {} {} (month must come first)",
+                        first, second
+                    ),
+                )
+                .with_hint(Some(format!(
+                    "for {} {} use `cal {} {}`",
+                    get_month_name(month),
+                    first,
+                    second,
+                    first
+                ))));
             }
-            Ok((year, month, None))
+            let month = parse_month_arg(first)?;
+            let year = parse_year(second)?;
+            Ok(DisplayDate::month(year, month))
         }
         // Three arguments: day month year
         (true, true, true) => {
-            let day = args
-                .day_arg
-                .as_ref()
-                .unwrap()
+            let day_str = args.day_arg.as_ref().unwrap();
+            let day = day_str
                 .parse::<u32>()
-                .map_err(|_| format!("Invalid day: {}", args.day_arg.as_ref().unwrap()))?;
+                .map_err(|_| CalError::new(ErrorKind::Day, format!("Invalid day: {}", day_str)))?;
             if !(1..=31).contains(&day) {
-                return Err(format!("Invalid day: {} (must be 1-31)", day));
+                return Err(CalError::new(
+                    ErrorKind::Day,
+                    format!("Invalid day: {} (must be 1-31)", day),
+                ));
             }
-            let month = crate::formatter::parse_month(args.month_arg.as_ref().unwrap())
-                .ok_or_else(|| format!("Invalid month: {}", args.month_arg.as_ref().unwrap()))?;
-            let year = args
-                .year_arg
-                .as_ref()
-                .unwrap()
-                .parse::<i32>()
-                .map_err(|_| format!("Invalid year: {}", args.year_arg.as_ref().unwrap()))?;
-            if !(1..=9999).contains(&year) {
-                return Err(format!("Invalid year: {} (must be 1-9999)", year));
-            }
-            Ok((year, month, Some(day)))
+            let month = parse_month_arg(args.month_arg.as_ref().unwrap())?;
+            let year = parse_year(args.year_arg.as_ref().unwrap())?;
+            Ok(DisplayDate::day(year, month, day))
         }
         // No arguments: current month
-        (false, false, false) => Ok((today.year(), today.month(), None)),
+        (false, false, false) => Ok(DisplayDate::month(today.year(), today.month())),
         // Invalid combinations
-        _ => Err("Invalid argument combination".to_string()),
+        _ => Err(CalError::new(
+            ErrorKind::Argument,
+            "Invalid argument combination",
+        )),
+    }
+}
+
+/// Keywords of relative arguments, offered as suggestions for typos.
+const RELATIVE_WORDS: &[&str] = &["next", "last", "prev", "previous", "this"];
+
+/// Units of relative arguments.
+const RELATIVE_UNITS: &[&str] = &["month", "months", "year", "years"];
+
+/// "did you mean ...?" for the closest word, if any.
+fn did_you_mean(val: &str, words: &[&str]) -> Option<String> {
+    closest(val, words.iter().copied()).map(|word| format!("did you mean {}?", word))
+}
+
+/// Parse a month argument (number or name), suggesting the closest name on a typo.
+fn parse_month_arg(val: &str) -> Result<u32, CalError> {
+    crate::formatter::parse_month(val).ok_or_else(|| match val.parse::<i32>() {
+        Ok(_) => CalError::new(
+            ErrorKind::Month,
+            format!("Invalid month: {} (must be 1-12)", val),
+        ),
+        Err(_) => CalError::new(ErrorKind::Month, format!("Invalid month: {}", val))
+            .with_hint(suggest_month(val).map(|month| format!("did you mean {}?", month))),
+    })
+}
+
+/// Parse a year argument in 1-9999.
+pub fn parse_year(val: &str) -> Result<i32, CalError> {
+    match val.parse::<i32>() {
+        Ok(year) if (1..=9999).contains(&year) => Ok(year),
+        Ok(_) => Err(CalError::new(
+            ErrorKind::Year,
+            format!("Invalid year: {} (must be 1-9999)", val),
+        )),
+        Err(_) => Err(CalError::new(
+            ErrorKind::Year,
+            format!("Invalid year: {}", val),
+        )),
+    }
+}
+
+/// Year and week for --week: the current week, week N of the year argument (this year
+/// without one), or the current week number in a year given as `--week 2026`.
+pub fn week_to_show(args: &Args, ctx: &CalContext) -> Result<(i32, u32), CalError> {
+    let current = ctx.week_of(ctx.today);
+    match (args.week.flatten(), args.day_arg.as_deref()) {
+        (None, None) => Ok(current),
+        // No year has more than 53 weeks, so a larger number is the year
+        (Some(year), None) if year > 53 => Ok((parse_year(&year.to_string())?, current.1)),
+        (week, year) => {
+            let year = year
+                .map(parse_year)
+                .transpose()?
+                .unwrap_or(ctx.today.year());
+            Ok((year, week.unwrap_or(current.1)))
+        }
+    }
+}
+
+/// Reform year from --reform, overridden by --iso.
+fn reform_year(args: &Args) -> i32 {
+    if args.iso {
+        ReformType::Iso.reform_year()
+    } else {
+        args.reform.reform_year()
+    }
+}
+
+/// Parse a quarter ("Q1".."Q4") or half ("H1", "H2"), case-insensitive.
+fn parse_period(val: &str) -> Option<Period> {
+    let (kind, number) = val.split_at_checked(1)?;
+    let number: u32 = number.parse().ok()?;
+    match kind {
+        "Q" | "q" if (1..=4).contains(&number) => Some(Period::Quarter(number)),
+        "H" | "h" if (1..=2).contains(&number) => Some(Period::Half(number)),
+        _ => None,
+    }
+}
+
+/// Parse a range of months or whole years ("2026-03..2026-08", "2025..2027").
+///
+/// Returns `Ok(None)` when the text is not a range.
+fn parse_month_range(val: &str) -> Result<Option<DisplayDate>, CalError> {
+    // A bare year starts in January and ends in December
+    let parse_end = |text: &str, last: bool| -> Result<(i32, u32), CalError> {
+        if text.parse::<i32>().is_ok() {
+            return range_year(text).map(|year| (year, if last { 12 } else { 1 }));
+        }
+        match parse_date_string(text) {
+            Ok(Some(date)) if date.day.is_none() && text.contains('-') => {
+                Ok((date.year, date.month))
+            }
+            Ok(_) => Err(CalError::new(
+                ErrorKind::Argument,
+                format!("Invalid range bound: {} (expected YYYY or YYYY-MM)", text),
+            )),
+            Err(e) => Err(e),
+        }
+    };
+    let Some(((first_year, first_month), (last_year, last_month))) = parse_range(val, parse_end)?
+    else {
+        return Ok(None);
+    };
+    let count = (last_year * 12 + last_month as i32) - (first_year * 12 + first_month as i32) + 1;
+    Ok(Some(DisplayDate::range(
+        first_year,
+        first_month,
+        count as u32,
+    )))
+}
+
+/// Parse a relative argument ("next", "last", "this", "+N", "-N") with an optional unit.
+///
+/// Returns `Ok(None)` when the argument is not relative.
+fn parse_relative(
+    val: &str,
+    unit: Option<&str>,
+    today: chrono::NaiveDate,
+) -> Result<Option<DisplayDate>, CalError> {
+    let offset = match val.to_lowercase().as_str() {
+        "next" => 1,
+        "last" | "prev" | "previous" => -1,
+        "this" => 0,
+        v if v.starts_with(['+', '-']) => match v.parse::<i32>() {
+            Ok(n) => n,
+            Err(_) => return Ok(None),
+        },
+        _ => return Ok(None),
+    };
+
+    let (year, month) = (today.year(), today.month());
+    let date = match unit.map(str::to_lowercase).as_deref() {
+        None | Some("month" | "months") => {
+            let index = year * 12 + month as i32 - 1 + offset;
+            DisplayDate::month(index.div_euclid(12), index.rem_euclid(12) as u32 + 1)
+        }
+        Some("year" | "years") => DisplayDate::year(year + offset, month),
+        // "cal +2 2026" is month 2 of 2026, not an offset
+        Some(other) if other.parse::<i32>().is_ok() => return Ok(None),
+        Some(other) => {
+            return Err(CalError::new(
+                ErrorKind::Argument,
+                format!(
+                    "Invalid unit: {} (expected month or year, e.g. 'next year')",
+                    other
+                ),
+            )
+            .with_hint(did_you_mean(other, RELATIVE_UNITS)));
+        }
+    };
+    if !(1..=9999).contains(&date.year) {
+        return Err(CalError::new(
+            ErrorKind::Year,
+            format!("Invalid year: {} (must be 1-9999)", date.year),
+        ));
+    }
+    Ok(Some(date))
+}
+
+/// Parse a date string: ISO (2026-03, 2026-03-15), dotted (15.03.2026) or slashed (03/15/2026).
+///
+/// Slashed dates follow the locale order (month first for en_US, day first otherwise);
+/// without a locale they are accepted only when unambiguous. Returns `Ok(None)` when the
+/// text looks like none of these forms.
+fn parse_date_string(val: &str) -> Result<Option<DisplayDate>, CalError> {
+    let numbers = |sep: char| -> Option<Vec<u32>> {
+        let parts: Vec<&str> = val.split(sep).collect();
+        if parts.len() < 2 || parts.iter().any(|p| p.is_empty()) {
+            return None;
+        }
+        parts.iter().map(|p| p.parse().ok()).collect()
+    };
+    let invalid = || CalError::new(ErrorKind::Argument, format!("Invalid date: {}", val));
+
+    let (year, month, day) = if let Some(parts) = numbers('-') {
+        match parts[..] {
+            [year, month] => (year, month, None),
+            [year, month, day] => (year, month, Some(day)),
+            _ => return Err(invalid()),
+        }
+    } else if let Some(parts) = numbers('.') {
+        match parts[..] {
+            [month, year] => (year, month, None),
+            [day, month, year] => (year, month, Some(day)),
+            _ => return Err(invalid()),
+        }
+    } else if let Some(parts) = numbers('/') {
+        let [a, b, year] = parts[..] else {
+            return Err(invalid());
+        };
+        let (month, day) = match slash_date_order() {
+            Some(SlashOrder::MonthFirst) => (a, b),
+            Some(SlashOrder::DayFirst) => (b, a),
+            None if a == b || a > 12 => (b, a),
+            None if b > 12 => (a, b),
+            None => {
+                return Err(CalError::new(
+                    ErrorKind::Ambiguous,
+                    format!("Ambiguous date: {} (day and month could be swapped)", val),
+                )
+                .with_hint(Some(format!(
+                    "use {:04}-{:02}-{:02} or {:04}-{:02}-{:02}",
+                    year, a, b, year, b, a
+                ))));
+            }
+        };
+        (year, month, Some(day))
+    } else {
+        return Ok(None);
+    };
+
+    let year = year as i32;
+    if !(1..=9999).contains(&year) {
+        return Err(CalError::new(
+            ErrorKind::Year,
+            format!("Invalid year: {} (must be 1-9999)", year),
+        ));
+    }
+    if !(1..=12).contains(&month) {
+        return Err(CalError::new(
+            ErrorKind::Month,
+            format!("Invalid month: {} (must be 1-12)", month),
+        ));
+    }
+    Ok(Some(match day {
+        Some(day) if (1..=31).contains(&day) => DisplayDate::day(year, month, day),
+        Some(day) => {
+            return Err(CalError::new(
+                ErrorKind::Day,
+                format!("Invalid day: {} (must be 1-31)", day),
+            ));
+        }
+        None => DisplayDate::month(year, month),
+    }))
+}
+
+/// Order of day and month in slashed dates.
+enum SlashOrder {
+    MonthFirst,
+    DayFirst,
+}
+
+/// Get slashed date order from LC_ALL, LC_TIME or LANG (None for C/POSIX or unset).
+fn slash_date_order() -> Option<SlashOrder> {
+    let locale = ["LC_ALL", "LC_TIME", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())?;
+    if locale == "C" || locale == "POSIX" || locale.starts_with("C.") {
+        None
+    } else if locale.starts_with("en_US") {
+        Some(SlashOrder::MonthFirst)
+    } else {
+        Some(SlashOrder::DayFirst)
     }
 }
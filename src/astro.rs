@@ -0,0 +1,410 @@
+//! Astronomical calculations: equinoxes, solstices, sunrise and sunset.
+//!
+//! Equinoxes and solstices use the algorithms from Jean Meeus, "Astronomical
+//! Algorithms" (chapter 27), accurate to about a minute for years 1000-3000.
+//! Sunrise and sunset use the sunrise equation with the NOAA approximations.
+
+use chrono::{DateTime, Locale, Utc};
+
+/// Julian Day of the Unix epoch (1970-01-01 00:00 UTC).
+const UNIX_EPOCH_JD: f64 = 2_440_587.5;
+
+/// Julian Day of the J2000.0 epoch.
+const J2000_JD: f64 = 2_451_545.0;
+
+/// Equinoxes and solstices (named after the month they occur in).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Season {
+    /// March equinox (spring in the northern hemisphere).
+    MarchEquinox,
+    /// June solstice (summer in the northern hemisphere).
+    JuneSolstice,
+    /// September equinox (autumn in the northern hemisphere).
+    SeptemberEquinox,
+    /// December solstice (winter in the northern hemisphere).
+    DecemberSolstice,
+}
+
+impl Season {
+    /// All seasons in calendar order.
+    pub const ALL: [Season; 4] = [
+        Season::MarchEquinox,
+        Season::JuneSolstice,
+        Season::SeptemberEquinox,
+        Season::DecemberSolstice,
+    ];
+
+    /// Return the season occurring in the given month, if any.
+    pub fn for_month(month: u32) -> Option<Season> {
+        match month {
+            3 => Some(Season::MarchEquinox),
+            6 => Some(Season::JuneSolstice),
+            9 => Some(Season::SeptemberEquinox),
+            12 => Some(Season::DecemberSolstice),
+            _ => None,
+        }
+    }
+
+    /// Month in which the season begins.
+    pub fn month(self) -> u32 {
+        match self {
+            Season::MarchEquinox => 3,
+            Season::JuneSolstice => 6,
+            Season::SeptemberEquinox => 9,
+            Season::DecemberSolstice => 12,
+        }
+    }
+
+    /// Human-readable name in a locale (Russian, otherwise English).
+    pub fn name_in(self, locale: Locale) -> &'static str {
+        match (locale, self) {
+            (Locale::ru_RU, Season::MarchEquinox) => "мартовское равноденствие",
+            (Locale::ru_RU, Season::JuneSolstice) => "июньское солнцестояние",
+            (Locale::ru_RU, Season::SeptemberEquinox) => "сентябрьское равноденствие",
+            (Locale::ru_RU, Season::DecemberSolstice) => "декабрьское солнцестояние",
+            (_, Season::MarchEquinox) => "March equinox",
+            (_, Season::JuneSolstice) => "June solstice",
+            (_, Season::SeptemberEquinox) => "September equinox",
+            (_, Season::DecemberSolstice) => "December solstice",
+        }
+    }
+
+    /// Polynomial coefficients of the mean instant (Meeus tables 27.A and 27.B).
+    fn mean_coefficients(self, year: i32) -> [f64; 5] {
+        if year < 1000 {
+            match self {
+                Season::MarchEquinox => [1721139.29189, 365242.13740, 0.06134, 0.00111, -0.00071],
+                Season::JuneSolstice => [1721233.25401, 365241.72562, -0.05323, 0.00907, 0.00025],
+                Season::SeptemberEquinox => {
+                    [1721325.70455, 365242.49558, -0.11677, -0.00297, 0.00074]
+                }
+                Season::DecemberSolstice => {
+                    [1721414.39987, 365242.88257, -0.00769, -0.00933, -0.00006]
+                }
+            }
+        } else {
+            match self {
+                Season::MarchEquinox => [2451623.80984, 365242.37404, 0.05169, -0.00411, -0.00057],
+                Season::JuneSolstice => [2451716.56767, 365241.62603, 0.00325, 0.00888, -0.00030],
+                Season::SeptemberEquinox => {
+                    [2451810.21715, 365242.01767, -0.11575, 0.00337, 0.00078]
+                }
+                Season::DecemberSolstice => {
+                    [2451900.05952, 365242.74049, -0.06223, -0.00823, 0.00032]
+                }
+            }
+        }
+    }
+}
+
+/// Periodic terms for the equinox/solstice correction (Meeus table 27.C): A, B, C.
+const PERIODIC_TERMS: [(f64, f64, f64); 24] = [
+    (485.0, 324.96, 1934.136),
+    (203.0, 337.23, 32964.467),
+    (199.0, 342.08, 20.186),
+    (182.0, 27.85, 445267.112),
+    (156.0, 73.14, 45036.886),
+    (136.0, 171.52, 22518.443),
+    (77.0, 222.54, 65928.934),
+    (74.0, 296.72, 3034.906),
+    (70.0, 243.58, 9037.513),
+    (58.0, 119.81, 33718.147),
+    (52.0, 297.17, 150.678),
+    (50.0, 21.02, 2281.226),
+    (45.0, 247.54, 29929.562),
+    (44.0, 325.15, 31555.956),
+    (29.0, 60.93, 4443.417),
+    (18.0, 155.12, 67555.328),
+    (17.0, 288.79, 4562.452),
+    (16.0, 198.04, 62894.029),
+    (14.0, 199.76, 31436.921),
+    (12.0, 95.39, 14577.848),
+    (12.0, 287.11, 31931.756),
+    (12.0, 320.81, 34777.259),
+    (9.0, 227.73, 1222.114),
+    (8.0, 15.45, 16859.074),
+];
+
+/// Compute the instant of an equinox or solstice in the given (astronomical) year.
+pub fn season_instant(year: i32, season: Season) -> DateTime<Utc> {
+    let y = if year < 1000 {
+        year as f64 / 1000.0
+    } else {
+        (year - 2000) as f64 / 1000.0
+    };
+    let c = season.mean_coefficients(year);
+    let jde0 = c[0] + y * (c[1] + y * (c[2] + y * (c[3] + y * c[4])));
+
+    let t = (jde0 - J2000_JD) / 36525.0;
+    let w = (35999.373 * t - 2.47).to_radians();
+    let delta_lambda = 1.0 + 0.0334 * w.cos() + 0.0007 * (2.0 * w).cos();
+    let s: f64 = PERIODIC_TERMS
+        .iter()
+        .map(|&(a, b, c)| a * (b + c * t).to_radians().cos())
+        .sum();
+
+    // Ephemeris time -> universal time
+    let jde = jde0 + 0.00001 * s / delta_lambda;
+    let jd = jde - delta_t(year) / 86400.0;
+    julian_day_to_utc(jd)
+}
+
+/// Convert a (fractional) Julian Day to a UTC timestamp.
+pub fn julian_day_to_utc(jd: f64) -> DateTime<Utc> {
+    let seconds = ((jd - UNIX_EPOCH_JD) * 86400.0).round() as i64;
+    DateTime::from_timestamp(seconds, 0).unwrap_or_default()
+}
+
+/// Geographic location for sunrise and sunset calculation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Location {
+    /// Display name (city name or formatted coordinates).
+    pub name: String,
+    /// Latitude in degrees, positive north.
+    pub latitude: f64,
+    /// Longitude in degrees, positive east.
+    pub longitude: f64,
+}
+
+/// Built-in cities: (name, latitude, longitude).
+pub const CITIES: &[(&str, f64, f64)] = &[
+    ("Almaty", 43.24, 76.89),
+    ("Ankara", 39.93, 32.86),
+    ("Astana", 51.17, 71.45),
+    ("Beijing", 39.90, 116.41),
+    ("Berlin", 52.52, 13.40),
+    ("Istanbul", 41.01, 28.98),
+    ("Kazan", 55.79, 49.12),
+    ("Kyiv", 50.45, 30.52),
+    ("London", 51.51, -0.13),
+    ("Los Angeles", 34.05, -118.24),
+    ("Minsk", 53.90, 27.57),
+    ("Moscow", 55.75, 37.62),
+    ("Murmansk", 68.97, 33.08),
+    ("New York", 40.71, -74.01),
+    ("Norilsk", 69.35, 88.20),
+    ("Novosibirsk", 55.03, 82.92),
+    ("Paris", 48.86, 2.35),
+    ("Reykjavik", 64.15, -21.94),
+    ("Riga", 56.95, 24.11),
+    ("Saint Petersburg", 59.94, 30.31),
+    ("San Francisco", 37.77, -122.42),
+    ("Sydney", -33.87, 151.21),
+    ("Tashkent", 41.30, 69.24),
+    ("Tokyo", 35.68, 139.69),
+    ("Tromso", 69.65, 18.96),
+    ("Vladivostok", 43.12, 131.89),
+    ("Yakutsk", 62.03, 129.73),
+    ("Yekaterinburg", 56.84, 60.61),
+];
+
+impl Location {
+    /// Parse location from "LAT,LON" in decimal degrees or a built-in city name.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        if let Some((lat, lon)) = s.split_once(',')
+            && let (Ok(latitude), Ok(longitude)) =
+                (lat.trim().parse::<f64>(), lon.trim().parse::<f64>())
+        {
+            if !(-90.0..=90.0).contains(&latitude) {
+                return Err(format!("Invalid latitude: {} (must be -90..90)", latitude));
+            }
+            if !(-180.0..=180.0).contains(&longitude) {
+                return Err(format!(
+                    "Invalid longitude: {} (must be -180..180)",
+                    longitude
+                ));
+            }
+            return Ok(Location {
+                name: format_coordinates(latitude, longitude),
+                latitude,
+                longitude,
+            });
+        }
+
+        let wanted = normalize_city_name(s);
+        CITIES
+            .iter()
+            .find(|(name, _, _)| normalize_city_name(name) == wanted)
+            .map(|&(name, latitude, longitude)| Location {
+                name: name.to_string(),
+                latitude,
+                longitude,
+            })
+            .ok_or_else(|| format!("Unknown location: {} (use LAT,LON or a city name)", s))
+    }
+}
+
+/// Lowercase city name with spaces, dashes and underscores removed.
+fn normalize_city_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '_'))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Format coordinates as "55.75°N 37.62°E".
+fn format_coordinates(latitude: f64, longitude: f64) -> String {
+    format!(
+        "{:.2}°{} {:.2}°{}",
+        latitude.abs(),
+        if latitude < 0.0 { 'S' } else { 'N' },
+        longitude.abs(),
+        if longitude < 0.0 { 'W' } else { 'E' }
+    )
+}
+
+/// Sun visibility for a single day.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Daylight {
+    /// The sun rises and sets.
+    Normal {
+        sunrise: DateTime<Utc>,
+        sunset: DateTime<Utc>,
+    },
+    /// The sun stays above the horizon all day.
+    PolarDay,
+    /// The sun stays below the horizon all day.
+    PolarNight,
+}
+
+impl Daylight {
+    /// Day length in minutes.
+    pub fn length_minutes(&self) -> i64 {
+        match self {
+            Daylight::Normal { sunrise, sunset } => (*sunset - *sunrise).num_minutes(),
+            Daylight::PolarDay => 24 * 60,
+            Daylight::PolarNight => 0,
+        }
+    }
+
+    /// Whether the day is a polar day or polar night.
+    pub fn is_polar(&self) -> bool {
+        !matches!(self, Daylight::Normal { .. })
+    }
+
+    /// Name of a polar day or polar night in a locale (Russian, otherwise English).
+    pub fn polar_name_in(&self, locale: Locale) -> Option<&'static str> {
+        match (locale, self) {
+            (_, Daylight::Normal { .. }) => None,
+            (Locale::ru_RU, Daylight::PolarDay) => Some("полярный день"),
+            (Locale::ru_RU, Daylight::PolarNight) => Some("полярная ночь"),
+            (_, Daylight::PolarDay) => Some("polar day"),
+            (_, Daylight::PolarNight) => Some("polar night"),
+        }
+    }
+}
+
+/// Compute sunrise and sunset for a day given by its Julian Day Number.
+pub fn sun_times(jdn: i64, location: &Location) -> Daylight {
+    let n = jdn as f64 - J2000_JD + 0.0008;
+    let mean_solar_noon = n - location.longitude / 360.0;
+
+    let m = (357.5291 + 0.98560028 * mean_solar_noon).rem_euclid(360.0);
+    let m_rad = m.to_radians();
+    let center = 1.9148 * m_rad.sin() + 0.0200 * (2.0 * m_rad).sin() + 0.0003 * (3.0 * m_rad).sin();
+    let lambda = (m + center + 180.0 + 102.9372)
+        .rem_euclid(360.0)
+        .to_radians();
+    let transit = J2000_JD + mean_solar_noon + 0.0053 * m_rad.sin() - 0.0069 * (2.0 * lambda).sin();
+
+    let declination = (lambda.sin() * 23.4397_f64.to_radians().sin()).asin();
+    let latitude = location.latitude.to_radians();
+    // -0.833° accounts for atmospheric refraction and the solar disc radius
+    let cos_hour_angle = ((-0.833_f64).to_radians().sin() - latitude.sin() * declination.sin())
+        / (latitude.cos() * declination.cos());
+
+    if cos_hour_angle < -1.0 {
+        return Daylight::PolarDay;
+    }
+    if cos_hour_angle > 1.0 {
+        return Daylight::PolarNight;
+    }
+
+    let hour_angle = cos_hour_angle.acos().to_degrees();
+    Daylight::Normal {
+        sunrise: julian_day_to_utc(transit - hour_angle / 360.0),
+        sunset: julian_day_to_utc(transit + hour_angle / 360.0),
+    }
+}
+
+/// Approximate difference between terrestrial and universal time (ΔT) in seconds.
+///
+/// Polynomial expressions by Espenak and Meeus (NASA Five Millennium Canon).
+fn delta_t(year: i32) -> f64 {
+    let y = year as f64 + 0.5;
+    let long_term = |y: f64| {
+        let u = (y - 1820.0) / 100.0;
+        -20.0 + 32.0 * u * u
+    };
+    match year {
+        ..-500 => long_term(y),
+        -500..500 => {
+            let u = y / 100.0;
+            10583.6 - 1014.41 * u + 33.78311 * u.powi(2)
+                - 5.952053 * u.powi(3)
+                - 0.1798452 * u.powi(4)
+                + 0.022174192 * u.powi(5)
+                + 0.0090316521 * u.powi(6)
+        }
+        500..1600 => {
+            let u = (y - 1000.0) / 100.0;
+            1574.2 - 556.01 * u + 71.23472 * u.powi(2) + 0.319781 * u.powi(3)
+                - 0.8503463 * u.powi(4)
+                - 0.005050998 * u.powi(5)
+                + 0.0083572073 * u.powi(6)
+        }
+        1600..1700 => {
+            let t = y - 1600.0;
+            120.0 - 0.9808 * t - 0.01532 * t.powi(2) + t.powi(3) / 7129.0
+        }
+        1700..1800 => {
+            let t = y - 1700.0;
+            8.83 + 0.1603 * t - 0.0059285 * t.powi(2) + 0.00013336 * t.powi(3)
+                - t.powi(4) / 1174000.0
+        }
+        1800..1860 => {
+            let t = y - 1800.0;
+            13.72 - 0.332447 * t + 0.0068612 * t.powi(2) + 0.0041116 * t.powi(3)
+                - 0.00037436 * t.powi(4)
+                + 0.0000121272 * t.powi(5)
+                - 0.0000001699 * t.powi(6)
+                + 0.000000000875 * t.powi(7)
+        }
+        1860..1900 => {
+            let t = y - 1860.0;
+            7.62 + 0.5737 * t - 0.251754 * t.powi(2) + 0.01680668 * t.powi(3)
+                - 0.0004473624 * t.powi(4)
+                + t.powi(5) / 233174.0
+        }
+        1900..1920 => {
+            let t = y - 1900.0;
+            -2.79 + 1.494119 * t - 0.0598939 * t.powi(2) + 0.0061966 * t.powi(3)
+                - 0.000197 * t.powi(4)
+        }
+        1920..1941 => {
+            let t = y - 1920.0;
+            21.20 + 0.84493 * t - 0.076100 * t.powi(2) + 0.0020936 * t.powi(3)
+        }
+        1941..1961 => {
+            let t = y - 1950.0;
+            29.07 + 0.407 * t - t.powi(2) / 233.0 + t.powi(3) / 2547.0
+        }
+        1961..1986 => {
+            let t = y - 1975.0;
+            45.45 + 1.067 * t - t.powi(2) / 260.0 - t.powi(3) / 718.0
+        }
+        1986..2005 => {
+            let t = y - 2000.0;
+            63.86 + 0.3345 * t - 0.060374 * t.powi(2)
+                + 0.0017275 * t.powi(3)
+                + 0.000651814 * t.powi(4)
+                + 0.00002373599 * t.powi(5)
+        }
+        2005..2050 => {
+            let t = y - 2000.0;
+            62.92 + 0.32217 * t + 0.005589 * t.powi(2)
+        }
+        2050..2150 => long_term(y) - 0.5628 * (2150.0 - y),
+        _ => long_term(y),
+    }
+}
@@ -1,32 +1,43 @@
 //! Calendar calculation logic using Zeller's algorithm and custom reform handling.
 
-use chrono::{Datelike, NaiveDate, Weekday};
+use chrono::{Datelike, Days, NaiveDate, Weekday};
 
 use crate::types::{
-    CELLS_PER_MONTH, CalContext, ColumnsMode, MonthData, REFORM_FIRST_DAY, REFORM_LAST_DAY,
-    REFORM_MONTH, REFORM_YEAR_GB, WeekType,
+    CELLS_PER_MONTH, CalContext, ColumnsMode, LayoutMatch, MONTH_WIDTH, MONTH_WIDTH_JULIAN,
+    MONTH_WIDTH_VERTICAL, MONTH_WIDTH_WEEK_NUMBERS, MonthData, REFORM_FIRST_DAY, REFORM_LAST_DAY,
+    REFORM_MONTH, REFORM_YEAR_GB, WEEKDAY_COLUMN_WIDTH, WeekType,
 };
 
+/// Check if a year is a leap year: Julian before the reform year, Gregorian from it.
+pub fn is_leap_year_for(year: i32, reform_year: i32) -> bool {
+    if year < reform_year {
+        // Julian: every 4 years
+        year % 4 == 0
+    } else {
+        // Gregorian: divisible by 4, except centuries unless divisible by 400
+        (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0)
+    }
+}
+
+/// Number of days in a month for the given reform year.
+pub fn days_in_month_for(year: i32, month: u32, reform_year: i32) -> u32 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year_for(year, reform_year) => 29,
+        2 => 28,
+        _ => 30,
+    }
+}
+
 impl CalContext {
     /// Check if a year is a leap year according to the calendar rules.
     pub fn is_leap_year(&self, year: i32) -> bool {
-        if year < self.reform_year {
-            // Julian: every 4 years
-            year % 4 == 0
-        } else {
-            // Gregorian: divisible by 4, except centuries unless divisible by 400
-            (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0)
-        }
+        is_leap_year_for(year, self.reform_year)
     }
 
     pub fn days_in_month(&self, year: i32, month: u32) -> u32 {
-        match month {
-            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
-            4 | 6 | 9 | 11 => 30,
-            2 if self.is_leap_year(year) => 29,
-            2 => 28,
-            _ => 30,
-        }
+        days_in_month_for(year, month, self.reform_year)
     }
 
     /// Check if a date falls within the reform gap (September 3-13, 1752).
@@ -39,6 +50,13 @@ impl CalContext {
             && (REFORM_FIRST_DAY..=REFORM_LAST_DAY).contains(&day)
     }
 
+    /// Check if a date falls under the Julian calendar: anything before the reform gap.
+    pub fn is_julian_date(&self, year: i32, month: u32, day: u32) -> bool {
+        year < self.reform_year
+            || (year == self.reform_year
+                && (month < REFORM_MONTH || (month == REFORM_MONTH && day < REFORM_FIRST_DAY)))
+    }
+
     /// Calculate weekday using Zeller's congruence algorithm.
     pub fn first_day_of_month(&self, year: i32, month: u32) -> Weekday {
         let m = if month < 3 { month + 12 } else { month };
@@ -47,9 +65,9 @@ impl CalContext {
         let k: i32 = year_i % 100;
         let j: i32 = year_i / 100;
 
-        let h = if year < self.reform_year {
-            // Julian calendar: no century correction
-            (q + (13 * (m as i32 + 1)) / 5 + k + k / 4 + 5).rem_euclid(7)
+        let h = if self.is_julian_date(year, month, 1) {
+            // Julian calendar: one day back per century, no 400-year correction
+            (q + (13 * (m as i32 + 1)) / 5 + k + k / 4 + 5 - j).rem_euclid(7)
         } else {
             // Gregorian calendar
             (q + (13 * (m as i32 + 1)) / 5 + k + k / 4 + j / 4 - 2 * j).rem_euclid(7)
@@ -84,6 +102,109 @@ impl CalContext {
         doy
     }
 
+    /// Calculate the Julian Day Number of a date in the active calendar.
+    pub fn julian_day_number(&self, year: i32, month: u32, day: u32) -> i64 {
+        julian_day_number(year, month, day, !self.is_julian_date(year, month, day))
+    }
+
+    /// Convert a Julian Day Number to a (year, month, day) in the active calendar.
+    pub fn date_from_julian_day_number(&self, jdn: i64) -> (i32, u32, u32) {
+        let (year, month, day) = date_from_julian_day_number(jdn, false);
+        if self.is_julian_date(year, month, day) {
+            (year, month, day)
+        } else {
+            date_from_julian_day_number(jdn, true)
+        }
+    }
+
+    /// Weekday of a date in the active calendar.
+    pub fn weekday(&self, year: i32, month: u32, day: u32) -> Weekday {
+        match (self.julian_day_number(year, month, day) + 1).rem_euclid(7) {
+            0 => Weekday::Sun,
+            n => Weekday::try_from(n as u8 - 1).unwrap(),
+        }
+    }
+
+    /// Convert a date in the active calendar to a proleptic Gregorian date (as used by chrono).
+    pub fn to_gregorian(&self, year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+        let days_from_ce = self.julian_day_number(year, month, day) - 1_721_425;
+        NaiveDate::from_num_days_from_ce_opt(i32::try_from(days_from_ce).ok()?)
+    }
+
+    /// Convert a proleptic Gregorian date (as used by chrono) to the active calendar.
+    pub fn from_gregorian(&self, date: NaiveDate) -> (i32, u32, u32) {
+        self.date_from_julian_day_number(date.num_days_from_ce() as i64 + 1_721_425)
+    }
+
+    /// Number of days in a year, excluding the reform gap.
+    pub fn days_in_year(&self, year: i32) -> u32 {
+        self.day_of_year(year, 12, 31)
+    }
+
+    /// Easter Sunday as (month, day), Julian computus before the reform.
+    pub fn easter(&self, year: i32) -> (u32, u32) {
+        if year < self.reform_year {
+            return julian_easter(year);
+        }
+        // Anonymous Gregorian algorithm
+        let a = year % 19;
+        let (b, c) = (year / 100, year % 100);
+        let (d, e) = (b / 4, b % 4);
+        let f = (b + 8) / 25;
+        let g = (b - f + 1) / 3;
+        let h = (19 * a + b - d - g + 15) % 30;
+        let (i, k) = (c / 4, c % 4);
+        let l = (32 + 2 * e + 2 * i - h - k) % 7;
+        let m = (a + 11 * h + 22 * l) / 451;
+        let (month, day) = ((h + l - 7 * m + 114) / 31, (h + l - 7 * m + 114) % 31 + 1);
+        (month as u32, day as u32)
+    }
+
+    /// Orthodox Easter Sunday (Julian computus) as (month, day) in the active calendar.
+    pub fn orthodox_easter(&self, year: i32) -> (u32, u32) {
+        let (month, day) = julian_easter(year);
+        let (_, month, day) =
+            self.date_from_julian_day_number(julian_day_number(year, month, day, false));
+        (month, day)
+    }
+
+    /// Compare the calendar layouts of two years.
+    ///
+    /// A year containing the reform gap only matches itself from March on.
+    pub fn layout_match(&self, year: i32, other: i32) -> Option<LayoutMatch> {
+        let has_gap = |y| self.is_reform_gap(y, REFORM_MONTH, REFORM_FIRST_DAY);
+        let jan_feb = self.first_day_of_month(year, 1) == self.first_day_of_month(other, 1);
+        let mar_dec = self.first_day_of_month(year, 3) == self.first_day_of_month(other, 3)
+            && (year == other || !has_gap(year) && !has_gap(other));
+
+        let same_leap = self.is_leap_year(year) == self.is_leap_year(other);
+
+        match (jan_feb, mar_dec) {
+            (true, true) if same_leap => Some(LayoutMatch::Full),
+            (true, _) if same_leap => Some(LayoutMatch::JanFeb),
+            (true, _) => Some(LayoutMatch::January),
+            (false, true) => Some(LayoutMatch::MarDec),
+            (false, false) => None,
+        }
+    }
+
+    /// Find years in an inclusive range whose calendar matches `year`.
+    ///
+    /// Partial matches are included only when `partial` is set; `year` itself is skipped.
+    pub fn same_layout_years(
+        &self,
+        year: i32,
+        first_year: i32,
+        last_year: i32,
+        partial: bool,
+    ) -> Vec<(i32, LayoutMatch)> {
+        (first_year..=last_year)
+            .filter(|&other| other != year)
+            .filter_map(|other| Some((other, self.layout_match(year, other)?)))
+            .filter(|&(_, kind)| partial || kind == LayoutMatch::Full)
+            .collect()
+    }
+
     pub fn week_number(&self, year: i32, month: u32, day: u32) -> u32 {
         match self.week_type {
             WeekType::Iso => {
@@ -102,16 +223,66 @@ impl CalContext {
         }
     }
 
+    /// Dates of a week in a year under the active week numbering.
+    pub fn week_dates(&self, year: i32, week: u32) -> Option<[NaiveDate; 7]> {
+        let first = match self.week_type {
+            WeekType::Iso => NaiveDate::from_isoywd_opt(year, week, Weekday::Mon)?,
+            WeekType::Us => {
+                // Week 1 is the Sunday-started week containing January 1
+                let jan1 = NaiveDate::from_ymd_opt(year, 1, 1)?;
+                let start = jan1 - Days::new(jan1.weekday().num_days_from_sunday() as u64);
+                let first = start.checked_add_days(Days::new(7 * (week.checked_sub(1)? as u64)))?;
+                if first.year() > year {
+                    return None;
+                }
+                first
+            }
+        };
+        Some(std::array::from_fn(|i| first + Days::new(i as u64)))
+    }
+
+    /// Week-numbering year and week number containing a date.
+    pub fn week_of(&self, date: NaiveDate) -> (i32, u32) {
+        match self.week_type {
+            WeekType::Iso => (date.iso_week().year(), date.iso_week().week()),
+            WeekType::Us => (
+                date.year(),
+                self.week_number(date.year(), date.month(), date.day()),
+            ),
+        }
+    }
+
     pub fn is_weekend(&self, weekday: Weekday) -> bool {
         matches!(weekday, Weekday::Sat | Weekday::Sun)
     }
 
+    /// Display width of one month in the horizontal layout.
+    pub fn month_width(&self) -> usize {
+        if self.julian {
+            MONTH_WIDTH_JULIAN
+        } else if self.week_numbers {
+            MONTH_WIDTH_WEEK_NUMBERS
+        } else {
+            MONTH_WIDTH
+        }
+    }
+
+    /// Display width of a row of months side by side, weekday column included when vertical.
+    pub fn row_width(&self, months: usize) -> usize {
+        let gutters = months.saturating_sub(1) * self.gutter_width;
+        if self.vertical {
+            WEEKDAY_COLUMN_WIDTH + months * MONTH_WIDTH_VERTICAL + gutters
+        } else {
+            months * self.month_width() + gutters
+        }
+    }
+
     pub fn months_per_row(&self) -> u32 {
         match self.columns {
             ColumnsMode::Fixed(n) => n,
             ColumnsMode::Auto => {
                 // ~20 chars per month + gutter, clamp to 1-3 for readability
-                let month_width = 20 + self.gutter_width;
+                let month_width = MONTH_WIDTH + self.gutter_width;
                 if let Some(term_width) = get_terminal_width() {
                     (term_width / month_width as u32).clamp(1, 3)
                 } else {
@@ -129,12 +300,7 @@ impl MonthData {
         let first_day = ctx.first_day_of_month(year, month);
 
         // Calculate offset based on week start day
-        let offset = match ctx.week_start {
-            Weekday::Mon if first_day == Weekday::Sun => 6,
-            Weekday::Mon => first_day.num_days_from_monday() as usize,
-            Weekday::Sun => first_day.num_days_from_sunday() as usize,
-            _ => unreachable!(),
-        };
+        let offset = first_day.days_since(ctx.week_start) as usize;
 
         let mut days: Vec<Option<u32>> = Vec::with_capacity(CELLS_PER_MONTH);
         let mut week_numbers: Vec<Option<u32>> = Vec::with_capacity(CELLS_PER_MONTH);
@@ -152,13 +318,7 @@ impl MonthData {
         let mut day = 1;
         while day <= days_in_month {
             if ctx.is_reform_gap(year, month, day) {
-                // Skip reform gap (3-13 September 1752)
-                for _ in REFORM_FIRST_DAY..=REFORM_LAST_DAY {
-                    days.push(None);
-                    week_numbers.push(None);
-                    weekdays.push(None);
-                    current_weekday = current_weekday.succ();
-                }
+                // Skip reform gap (3-13 September 1752): the 14th follows the 2nd
                 day = REFORM_LAST_DAY + 1;
             } else {
                 days.push(Some(day));
@@ -186,6 +346,43 @@ impl MonthData {
     }
 }
 
+/// Easter Sunday as a Julian calendar (month, day), Meeus Julian algorithm.
+pub fn julian_easter(year: i32) -> (u32, u32) {
+    let (a, b, c) = (year % 4, year % 7, year % 19);
+    let d = (19 * c + 15) % 30;
+    let e = (2 * a + 4 * b - d + 34) % 7;
+    (((d + e + 114) / 31) as u32, ((d + e + 114) % 31 + 1) as u32)
+}
+
+/// Julian Day Number of a date in the Gregorian or Julian calendar.
+pub fn julian_day_number(year: i32, month: u32, day: u32, gregorian: bool) -> i64 {
+    let a = (14 - month as i64) / 12;
+    let y = year as i64 + 4800 - a;
+    let m = month as i64 + 12 * a - 3;
+    let jdn = day as i64 + (153 * m + 2) / 5 + 365 * y + y / 4;
+    if gregorian {
+        jdn - y / 100 + y / 400 - 32045
+    } else {
+        jdn - 32083
+    }
+}
+
+/// Convert a Julian Day Number to a Julian or Gregorian (year, month, day).
+pub fn date_from_julian_day_number(jdn: i64, gregorian: bool) -> (i32, u32, u32) {
+    // Richards' algorithm
+    let mut f = jdn + 1401;
+    if gregorian {
+        f += (((4 * jdn + 274277) / 146097) * 3) / 4 - 38;
+    }
+    let e = 4 * f + 3;
+    let g = (e % 1461) / 4;
+    let h = 5 * g + 2;
+    let day = (h % 153) / 5 + 1;
+    let month = (h / 153 + 2) % 12 + 1;
+    let year = e / 1461 - 4716 + (12 + 2 - month) / 12;
+    (year as i32, month as u32, day as u32)
+}
+
 /// Get terminal width using terminal_size crate.
 fn get_terminal_width() -> Option<u32> {
     terminal_size::terminal_size().map(|(w, _)| w.0 as u32)
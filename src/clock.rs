@@ -0,0 +1,71 @@
+//! Source of the current time, so that "today" can be fixed for tests and scripts.
+
+use chrono::{DateTime, Local, NaiveDate, Offset, TimeDelta, TimeZone as _, Utc};
+
+use crate::zoneinfo::TimeZone;
+
+/// Clock that tells the current instant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Clock {
+    /// The system clock.
+    #[default]
+    System,
+    /// A fixed instant.
+    Fixed(DateTime<Utc>),
+    /// The system clock shifted by a duration.
+    Offset(TimeDelta),
+}
+
+impl Clock {
+    /// Clock fixed at noon of the date in the zone (local time if none),
+    /// so that the date also holds in neighbouring zones.
+    pub fn at_date(date: NaiveDate, zone: Option<&TimeZone>) -> Self {
+        let noon = date.and_hms_opt(12, 0, 0).unwrap();
+        let offset = match zone {
+            Some(zone) => zone.local_type_at(noon.and_utc().timestamp()).utc_offset,
+            None => Local
+                .offset_from_utc_datetime(&noon)
+                .fix()
+                .local_minus_utc(),
+        };
+        Clock::Fixed(noon.and_utc() - TimeDelta::seconds(offset.into()))
+    }
+
+    /// Clock from CAL_TEST_TIME: seconds since the epoch as in util-linux, or a
+    /// YYYY-MM-DD date in the zone. The system clock when unset or invalid.
+    pub fn from_env(zone: Option<&TimeZone>) -> Self {
+        std::env::var("CAL_TEST_TIME")
+            .ok()
+            .and_then(|value| Self::parse(&value, zone))
+            .unwrap_or_default()
+    }
+
+    /// Parse seconds since the epoch or a YYYY-MM-DD date.
+    pub fn parse(value: &str, zone: Option<&TimeZone>) -> Option<Self> {
+        let value = value.trim();
+        if let Ok(seconds) = value.parse::<i64>() {
+            return DateTime::from_timestamp(seconds, 0).map(Clock::Fixed);
+        }
+        NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .ok()
+            .map(|date| Self::at_date(date, zone))
+    }
+
+    /// The current instant.
+    pub fn now(&self) -> DateTime<Utc> {
+        match self {
+            Clock::System => Utc::now(),
+            Clock::Fixed(instant) => *instant,
+            Clock::Offset(delta) => Utc::now() + *delta,
+        }
+    }
+
+    /// Today's date in the zone, or in local time.
+    pub fn today(&self, zone: Option<&TimeZone>) -> NaiveDate {
+        let now = self.now();
+        match zone {
+            Some(zone) => zone.to_local(now).date_naive(),
+            None => now.with_timezone(&Local).date_naive(),
+        }
+    }
+}
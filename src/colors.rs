@@ -0,0 +1,478 @@
+//! Color palette: built-in themes, util-linux terminal-colors.d schemes and CAL_COLORS.
+
+use std::path::{Path, PathBuf};
+
+use crate::types::{
+    COLOR_BLACK_ON_YELLOW, COLOR_MAGENTA, COLOR_RED, COLOR_REVERSE, COLOR_SAND_YELLOW, COLOR_TEAL,
+    COLOR_UNDERLINE, Theme,
+};
+
+/// Color sequences for logical calendar elements.
+///
+/// Names follow util-linux cal (`today`, `weeknumber`, `header`, `workday`, `weekend`),
+/// plus `weekhighlight` for the week picked with -w, `weekdays`, `holiday`, `shortened`,
+/// the project `release` and `freeze`, and `selected`, `marked` and `othertoday` days.
+/// An empty sequence means no color.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Palette {
+    pub today: String,
+    pub weeknumber: String,
+    pub weekhighlight: String,
+    pub header: String,
+    pub weekdays: String,
+    pub workday: String,
+    pub weekend: String,
+    pub holiday: String,
+    pub shortened: String,
+    pub release: String,
+    pub freeze: String,
+    /// Day given on the command line.
+    pub selected: String,
+    /// Days marked by --find, --seasons, --polar and --dst.
+    pub marked: String,
+    /// Today in another --zone.
+    pub othertoday: String,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self {
+            today: COLOR_REVERSE.to_string(),
+            weeknumber: String::new(),
+            weekhighlight: "\x1b[100m".to_string(),
+            header: COLOR_TEAL.to_string(),
+            weekdays: COLOR_SAND_YELLOW.to_string(),
+            workday: String::new(),
+            weekend: COLOR_RED.to_string(),
+            holiday: COLOR_RED.to_string(),
+            shortened: COLOR_TEAL.to_string(),
+            release: "\x1b[1;95m".to_string(),
+            freeze: COLOR_UNDERLINE.to_string(),
+            selected: COLOR_BLACK_ON_YELLOW.to_string(),
+            marked: COLOR_MAGENTA.to_string(),
+            othertoday: COLOR_UNDERLINE.to_string(),
+        }
+    }
+}
+
+/// util-linux color names and their sequences.
+const COLOR_NAMES: &[(&str, &str)] = &[
+    ("black", "\x1b[30m"),
+    ("blink", "\x1b[5m"),
+    ("blue", "\x1b[34m"),
+    ("bold", "\x1b[1m"),
+    ("brown", "\x1b[33m"),
+    ("cyan", "\x1b[36m"),
+    ("darkgray", "\x1b[1;30m"),
+    ("gray", "\x1b[37m"),
+    ("green", "\x1b[32m"),
+    ("halfbright", "\x1b[2m"),
+    ("lightblue", "\x1b[1;34m"),
+    ("lightcyan", "\x1b[1;36m"),
+    ("lightgray", "\x1b[37m"),
+    ("lightgreen", "\x1b[1;32m"),
+    ("lightmagenta", "\x1b[1;35m"),
+    ("lightred", "\x1b[1;31m"),
+    ("magenta", "\x1b[35m"),
+    ("red", "\x1b[31m"),
+    ("reset", "\x1b[0m"),
+    ("reverse", "\x1b[7m"),
+    ("underline", "\x1b[4m"),
+    ("white", "\x1b[1;37m"),
+    ("yellow", "\x1b[1;33m"),
+];
+
+/// Number of colors the terminal can show.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorDepth {
+    /// The 16 standard ANSI colors.
+    Basic,
+    /// The xterm 256-color palette.
+    Ansi256,
+    /// 24-bit RGB colors.
+    TrueColor,
+}
+
+impl ColorDepth {
+    /// Detect color depth from COLORTERM and TERM.
+    pub fn detect(colorterm: Option<&str>, term: Option<&str>) -> Self {
+        if matches!(colorterm, Some("truecolor" | "24bit")) {
+            ColorDepth::TrueColor
+        } else if term.is_some_and(|term| term.contains("256color")) {
+            ColorDepth::Ansi256
+        } else {
+            ColorDepth::Basic
+        }
+    }
+
+    /// Foreground color at this depth: a basic sequence, a 256-color index or RGB.
+    fn fg(self, basic: &str, index: u8, (r, g, b): (u8, u8, u8)) -> String {
+        match self {
+            ColorDepth::Basic => basic.to_string(),
+            ColorDepth::Ansi256 => format!("\x1b[38;5;{}m", index),
+            ColorDepth::TrueColor => format!("\x1b[38;2;{};{};{}m", r, g, b),
+        }
+    }
+}
+
+impl Palette {
+    /// Resolve the theme, apply the terminal-colors.d scheme file, then CAL_COLORS.
+    ///
+    /// Without a theme only COLORFGBG picks the light palette; the terminal is
+    /// queried only when auto is asked for.
+    pub fn load(theme: Option<Theme>, scheme: Option<&Path>) -> Self {
+        let term = std::env::var("TERM").ok();
+        let colorterm = std::env::var("COLORTERM").ok();
+        let theme = match theme {
+            Some(Theme::Auto) if terminal_is_light() => Theme::SolarizedLight,
+            None if colorfgbg_is_light() => Theme::SolarizedLight,
+            Some(Theme::Auto) | None => Theme::Default,
+            Some(theme) => theme,
+        };
+        let mut palette = Palette::theme(
+            theme,
+            ColorDepth::detect(colorterm.as_deref(), term.as_deref()),
+        );
+        if let Some(path) = scheme
+            && let Ok(text) = std::fs::read_to_string(path)
+        {
+            palette.apply_scheme(&text);
+        }
+        if let Ok(spec) = std::env::var("CAL_COLORS") {
+            palette.apply_cal_colors(&spec);
+        }
+        palette
+    }
+
+    /// Palette of a built-in theme (Auto gives the default palette).
+    pub fn theme(theme: Theme, depth: ColorDepth) -> Self {
+        let reverse = COLOR_REVERSE.to_string();
+        match theme {
+            Theme::Auto | Theme::Default => Palette::default(),
+            Theme::Mono => Palette {
+                today: reverse,
+                weeknumber: "\x1b[2m".to_string(),
+                weekhighlight: "\x1b[3;4m".to_string(),
+                header: "\x1b[1m".to_string(),
+                weekdays: "\x1b[4m".to_string(),
+                workday: String::new(),
+                weekend: "\x1b[1m".to_string(),
+                holiday: "\x1b[1;4m".to_string(),
+                shortened: "\x1b[4m".to_string(),
+                release: "\x1b[3m".to_string(),
+                freeze: COLOR_UNDERLINE.to_string(),
+                selected: "\x1b[1;7m".to_string(),
+                marked: "\x1b[1m".to_string(),
+                othertoday: COLOR_UNDERLINE.to_string(),
+            },
+            Theme::SolarizedLight => {
+                let red = depth.fg("\x1b[31m", 160, (220, 50, 47));
+                Palette {
+                    today: reverse,
+                    weeknumber: depth.fg("\x1b[2m", 245, (147, 161, 161)),
+                    weekhighlight: "\x1b[47m".to_string(),
+                    header: depth.fg("\x1b[34m", 33, (38, 139, 210)),
+                    weekdays: depth.fg("\x1b[90m", 240, (88, 110, 117)),
+                    workday: String::new(),
+                    weekend: red.clone(),
+                    holiday: red,
+                    shortened: depth.fg("\x1b[36m", 37, (42, 161, 152)),
+                    release: depth.fg("\x1b[35m", 125, (211, 54, 130)),
+                    freeze: COLOR_UNDERLINE.to_string(),
+                    selected: "\x1b[30;43m".to_string(),
+                    marked: depth.fg("\x1b[35m", 61, (108, 113, 196)),
+                    othertoday: COLOR_UNDERLINE.to_string(),
+                }
+            }
+            Theme::HighContrast => {
+                let red = format!("\x1b[1m{}", depth.fg("\x1b[91m", 196, (255, 0, 0)));
+                Palette {
+                    today: "\x1b[1;7m".to_string(),
+                    weeknumber: "\x1b[1m".to_string(),
+                    weekhighlight: "\x1b[1;4m".to_string(),
+                    header: "\x1b[1;4m".to_string(),
+                    weekdays: "\x1b[1m".to_string(),
+                    workday: String::new(),
+                    weekend: red.clone(),
+                    holiday: red,
+                    shortened: format!("\x1b[1m{}", depth.fg("\x1b[95m", 201, (255, 0, 255))),
+                    release: format!("\x1b[1m{}", depth.fg("\x1b[94m", 33, (0, 135, 255))),
+                    freeze: COLOR_UNDERLINE.to_string(),
+                    selected: "\x1b[1;30;103m".to_string(),
+                    marked: format!("\x1b[1m{}", depth.fg("\x1b[96m", 51, (0, 255, 255))),
+                    othertoday: "\x1b[1;4m".to_string(),
+                }
+            }
+            Theme::Colorblind => Palette {
+                today: reverse,
+                weeknumber: String::new(),
+                weekhighlight: "\x1b[100m".to_string(),
+                header: depth.fg("\x1b[34m", 25, (0, 114, 178)),
+                weekdays: "\x1b[1m".to_string(),
+                workday: String::new(),
+                weekend: depth.fg("\x1b[33m", 214, (230, 159, 0)),
+                holiday: depth.fg("\x1b[1;33m", 166, (213, 94, 0)),
+                shortened: depth.fg("\x1b[36m", 74, (86, 180, 233)),
+                release: depth.fg("\x1b[35m", 175, (204, 121, 167)),
+                freeze: COLOR_UNDERLINE.to_string(),
+                selected: COLOR_BLACK_ON_YELLOW.to_string(),
+                marked: depth.fg("\x1b[32m", 36, (0, 158, 115)),
+                othertoday: COLOR_UNDERLINE.to_string(),
+            },
+        }
+    }
+
+    /// Set an element by logical name; returns false for unknown names.
+    pub fn set(&mut self, name: &str, sequence: String) -> bool {
+        let slot = match name {
+            "today" => &mut self.today,
+            "weeknumber" => &mut self.weeknumber,
+            "weekhighlight" => &mut self.weekhighlight,
+            "header" => &mut self.header,
+            "weekdays" => &mut self.weekdays,
+            "workday" => &mut self.workday,
+            "weekend" => &mut self.weekend,
+            "holiday" => &mut self.holiday,
+            "shortened" => &mut self.shortened,
+            "release" => &mut self.release,
+            "freeze" => &mut self.freeze,
+            "selected" => &mut self.selected,
+            "marked" => &mut self.marked,
+            "othertoday" => &mut self.othertoday,
+            _ => return false,
+        };
+        *slot = sequence;
+        true
+    }
+
+    /// Apply a scheme file: "name color" per line, '#' starts a comment.
+    pub fn apply_scheme(&mut self, text: &str) {
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if let Some((name, value)) = line.split_once(char::is_whitespace)
+                && let Some(sequence) = parse_color(value.trim())
+            {
+                self.set(name, sequence);
+            }
+        }
+    }
+
+    /// Apply CAL_COLORS: "name=color" pairs separated by ':'.
+    pub fn apply_cal_colors(&mut self, spec: &str) {
+        for entry in spec.split(':') {
+            if let Some((name, value)) = entry.split_once('=')
+                && let Some(sequence) = parse_color(value.trim())
+            {
+                self.set(name.trim(), sequence);
+            }
+        }
+    }
+}
+
+/// Parse a color name, escaped sequence ("\e[1;31m", "\033[7m") or SGR parameters ("1;31").
+pub fn parse_color(value: &str) -> Option<String> {
+    if let Some(&(_, sequence)) = COLOR_NAMES.iter().find(|(name, _)| *name == value) {
+        return Some(sequence.to_string());
+    }
+    if !value.is_empty() && value.chars().all(|c| c.is_ascii_digit() || c == ';') {
+        return Some(format!("\x1b[{}m", value));
+    }
+    let sequence = value
+        .replace("\\033", "\x1b")
+        .replace("\\x1b", "\x1b")
+        .replace("\\e", "\x1b");
+    sequence.starts_with('\x1b').then_some(sequence)
+}
+
+/// Files for cal in terminal-colors.d, as util-linux reads them.
+#[derive(Debug, Default, PartialEq)]
+pub struct TerminalColors {
+    /// Most specific scheme file.
+    pub scheme: Option<PathBuf>,
+    /// Whether the most specific enable or disable file turns color on in auto mode.
+    pub enabled: Option<bool>,
+}
+
+impl TerminalColors {
+    /// Read the user directory, or /etc/terminal-colors.d when the user has none.
+    pub fn load(term: Option<&str>) -> Self {
+        colors_dir()
+            .map(|dir| Self::find(&dir, term))
+            .unwrap_or_default()
+    }
+
+    /// Find `[cal][@term].{scheme,enable,disable}` files in a directory.
+    ///
+    /// cal@term is the most specific name, then cal, @term and the bare type; a scheme
+    /// needs the cal name. Disable wins over an equally specific enable.
+    pub fn find(dir: &Path, term: Option<&str>) -> Self {
+        // Best score and path for scheme, enable and disable
+        let mut best: [Option<(u8, PathBuf)>; 3] = Default::default();
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return Self::default();
+        };
+        for path in entries.flatten().map(|entry| entry.path()) {
+            let Some(file) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            let (stem, kind) = file.rsplit_once('.').unwrap_or(("", file));
+            let slot = match kind {
+                "scheme" => 0,
+                "enable" => 1,
+                "disable" => 2,
+                _ => continue,
+            };
+            let (name, file_term) = stem.split_once('@').unwrap_or((stem, ""));
+            if !matches!(name, "" | "cal")
+                || !(file_term.is_empty() || Some(file_term) == term)
+                || (slot == 0 && name.is_empty())
+                || !path.is_file()
+            {
+                continue;
+            }
+            let score = 1 + 2 * u8::from(name == "cal") + u8::from(!file_term.is_empty());
+            if best[slot].as_ref().is_none_or(|(best, _)| score > *best) {
+                best[slot] = Some((score, path));
+            }
+        }
+        let [scheme, enable, disable] = best;
+        let enabled = match (enable, disable) {
+            (Some((enable, _)), Some((disable, _))) => Some(enable > disable),
+            (enable, disable) => enable.map(|_| true).or(disable.map(|_| false)),
+        };
+        TerminalColors {
+            scheme: scheme.map(|(_, path)| path),
+            enabled,
+        }
+    }
+}
+
+/// terminal-colors.d in XDG_CONFIG_HOME (or ~/.config), else /etc/terminal-colors.d.
+fn colors_dir() -> Option<PathBuf> {
+    let user = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+        .map(|config| config.join("terminal-colors.d"));
+    user.into_iter()
+        .chain([PathBuf::from("/etc/terminal-colors.d")])
+        .find(|dir| dir.is_dir())
+}
+
+/// Whether the terminal has a light background: COLORFGBG first, then an OSC 11 query.
+fn terminal_is_light() -> bool {
+    if let Some(light) = colorfgbg() {
+        return light;
+    }
+    query_background().is_some_and(is_light)
+}
+
+/// Whether COLORFGBG names a light background.
+fn colorfgbg_is_light() -> bool {
+    colorfgbg().unwrap_or(false)
+}
+
+fn colorfgbg() -> Option<bool> {
+    std::env::var("COLORFGBG")
+        .ok()
+        .and_then(|value| parse_colorfgbg(&value))
+}
+
+/// Parse COLORFGBG ("fg;bg" or "fg;default;bg"): light when the background is white or light gray.
+pub fn parse_colorfgbg(value: &str) -> Option<bool> {
+    let background: u8 = value.rsplit(';').next()?.parse().ok()?;
+    Some(matches!(background, 7 | 15))
+}
+
+/// Parse an OSC 11 reply ("\e]11;rgb:ffff/ffff/dddd\a") into RGB components in 0.0..=1.0.
+pub fn parse_osc11_reply(reply: &str) -> Option<(f64, f64, f64)> {
+    let start = reply.find("rgb:")? + 4;
+    let body = reply[start..].trim_end_matches(['\x07', '\x1b', '\\']);
+    let mut channels = body.split('/').map(|hex| {
+        let hex = hex.get(..hex.len().min(4))?;
+        let value = u32::from_str_radix(hex, 16).ok()?;
+        Some(value as f64 / ((1u32 << (4 * hex.len())) - 1) as f64)
+    });
+    let rgb = (channels.next()??, channels.next()??, channels.next()??);
+    channels.next().is_none().then_some(rgb)
+}
+
+/// Relative luminance above one half counts as a light background.
+pub fn is_light((r, g, b): (f64, f64, f64)) -> bool {
+    0.2126 * r + 0.7152 * g + 0.0722 * b > 0.5
+}
+
+/// Timeout for the terminal to answer the background color query.
+#[cfg(all(unix, feature = "terminal-query"))]
+const OSC11_TIMEOUT_MS: u128 = 100;
+
+/// Ask the terminal for its background color (OSC 11) through /dev/tty.
+#[cfg(all(unix, feature = "terminal-query"))]
+fn query_background() -> Option<(f64, f64, f64)> {
+    use std::io::{IsTerminal, Write};
+    use std::os::fd::AsRawFd;
+
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+    let mut tty = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+    let fd = tty.as_raw_fd();
+
+    // SAFETY: termios is plain data and fd stays open for the whole call.
+    let mut saved: libc::termios = unsafe { std::mem::zeroed() };
+    if unsafe { libc::tcgetattr(fd, &mut saved) } != 0 {
+        return None;
+    }
+    let mut raw = saved;
+    raw.c_lflag &= !(libc::ICANON | libc::ECHO);
+    raw.c_cc[libc::VMIN] = 0;
+    raw.c_cc[libc::VTIME] = 0;
+    if unsafe { libc::tcsetattr(fd, libc::TCSANOW, &raw) } != 0 {
+        return None;
+    }
+
+    let reply = tty
+        .write_all(b"\x1b]11;?\x07")
+        .and_then(|_| tty.flush())
+        .ok()
+        .map(|_| read_reply(fd));
+    unsafe { libc::tcsetattr(fd, libc::TCSANOW, &saved) };
+    parse_osc11_reply(&reply?)
+}
+
+#[cfg(not(all(unix, feature = "terminal-query")))]
+fn query_background() -> Option<(f64, f64, f64)> {
+    None
+}
+
+/// Read the terminal reply until BEL or ST, giving up after the timeout.
+#[cfg(all(unix, feature = "terminal-query"))]
+fn read_reply(fd: std::os::fd::RawFd) -> String {
+    let start = std::time::Instant::now();
+    let mut reply = Vec::new();
+    while reply.len() < 64 && !reply.ends_with(b"\x07") && !reply.ends_with(b"\x1b\\") {
+        let elapsed = start.elapsed().as_millis();
+        if elapsed >= OSC11_TIMEOUT_MS {
+            break;
+        }
+        let mut pollfd = libc::pollfd {
+            fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // SAFETY: pollfd and buf are valid for the duration of each call.
+        if unsafe { libc::poll(&mut pollfd, 1, (OSC11_TIMEOUT_MS - elapsed) as libc::c_int) } <= 0 {
+            break;
+        }
+        let mut buf = [0u8; 32];
+        let n = unsafe { libc::read(fd, buf.as_mut_ptr().cast(), buf.len()) };
+        if n <= 0 {
+            break;
+        }
+        reply.extend_from_slice(&buf[..n as usize]);
+    }
+    String::from_utf8_lossy(&reply).into_owned()
+}
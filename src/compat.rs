@@ -0,0 +1,423 @@
+//! util-linux compatible layout (--compat=util-linux, or POSIXLY_CORRECT when run as `cal`).
+//!
+//! Reproduces util-linux cal byte for byte: six week lines per month, cells padded with
+//! trailing spaces, month names left out of the year view and September 1752 drawn
+//! without the skipped days. Only today (or the selected day) and the -w week are highlighted.
+
+use chrono::NaiveDate;
+use std::io::{self, Write};
+use unicode_width::UnicodeWidthStr;
+
+use crate::formatter::{get_month_name, get_system_locale};
+use crate::types::{COLOR_RESET, CalContext, GUTTER_WIDTH_REGULAR, GUTTER_WIDTH_YEAR};
+
+const DAYS_IN_WEEK: usize = 7;
+const MAXDAYS: usize = 42;
+const WEEK_LINES: usize = MAXDAYS / DAYS_IN_WEEK;
+const DAY_LEN: usize = 3;
+const WNUM_LEN: usize = 3;
+
+/// Widths and header style shared by all months of one output.
+struct Layout {
+    /// Cell width including the separating space (3, or 4 with -j).
+    day_width: usize,
+    /// Width of one month in horizontal layout.
+    week_width: usize,
+    gutter_width: usize,
+    /// Year view: the year is printed once above the months.
+    header_year: bool,
+    /// "Month Year" does not fit the month width, so the year goes on a second line.
+    header_hint: bool,
+    months_in_row: usize,
+}
+
+impl Layout {
+    fn new(ctx: &CalContext, year: i32, gutter_width: usize, header_year: bool) -> Self {
+        let day_width = if ctx.julian { DAY_LEN + 1 } else { DAY_LEN };
+        let mut week_width = day_width * DAYS_IN_WEEK - 1;
+        if ctx.week_numbers {
+            week_width += WNUM_LEN;
+        }
+        let year_len = year.to_string().len();
+        let header_hint =
+            (1..=12).any(|month| week_width < get_month_name(month).width() + year_len + 1);
+        Layout {
+            day_width,
+            week_width,
+            gutter_width: if ctx.vertical { 1 } else { gutter_width },
+            header_year,
+            header_hint,
+            months_in_row: 1,
+        }
+    }
+}
+
+/// One month: 42 cells of (day of month, number shown) and six week numbers.
+struct Month {
+    year: i32,
+    month: u32,
+    days: [Option<(u32, u32)>; MAXDAYS],
+    weeks: [Option<u32>; WEEK_LINES],
+    /// Day of month to highlight.
+    highlight: Option<u32>,
+    /// Week line to highlight (-w).
+    highlight_line: Option<usize>,
+}
+
+impl Month {
+    /// Lay out days from the first weekday on; the reform gap takes no cells.
+    fn fill(ctx: &CalContext, year: i32, month: u32) -> Self {
+        let offset = ctx.weekday(year, month, 1).days_since(ctx.week_start) as usize;
+        // Day of year without the reform gap, as util-linux numbers -j days
+        let year_day: u32 = (1..month).map(|m| ctx.days_in_month(year, m)).sum();
+
+        let mut days = [None; MAXDAYS];
+        let shown = (1..=ctx.days_in_month(year, month))
+            .filter(|&day| !ctx.is_reform_gap(year, month, day))
+            .map(|day| (day, if ctx.julian { year_day + day } else { day }));
+        for (cell, day) in days.iter_mut().skip(offset).zip(shown) {
+            *cell = Some(day);
+        }
+
+        let weeks = std::array::from_fn(|line| {
+            let first = days[line * DAYS_IN_WEEK..(line + 1) * DAYS_IN_WEEK]
+                .iter()
+                .flatten()
+                .next()?;
+            ctx.week_numbers
+                .then(|| ctx.week_number(year, month, first.0))
+        });
+
+        let highlight_line = ctx.highlight_week.and_then(|week| {
+            (0..WEEK_LINES).find(|&line| {
+                days[line * DAYS_IN_WEEK..(line + 1) * DAYS_IN_WEEK]
+                    .iter()
+                    .flatten()
+                    .next()
+                    .and_then(|&(day, _)| NaiveDate::from_ymd_opt(year, month, day))
+                    .is_some_and(|date| ctx.week_of(date) == week)
+            })
+        });
+
+        let highlight = match ctx.selected {
+            Some((y, m, day)) if (y, m) == (year, month) => Some(day),
+            Some(_) => None,
+            None => {
+                let (y, m, day) = ctx.from_gregorian(ctx.today);
+                ((y, m) == (year, month)).then_some(day)
+            }
+        };
+
+        Month {
+            year,
+            month,
+            days,
+            weeks,
+            highlight,
+            highlight_line,
+        }
+    }
+}
+
+impl Month {
+    /// Color of a day: the today color for the highlighted day, else the week color on its line.
+    fn highlight_color<'a>(&self, ctx: &'a CalContext, day: u32, line: usize) -> Option<&'a str> {
+        if self.highlight == Some(day) {
+            Some(&ctx.palette.today)
+        } else if self.highlight_line == Some(line) {
+            Some(&ctx.palette.weekhighlight)
+        } else {
+            None
+        }
+    }
+}
+
+/// Center text, the odd space going to the left; longer text is cut.
+fn center(text: &str, width: usize) -> String {
+    let text: String = fit(text, width);
+    let spaces = width - text.width();
+    let left = spaces / 2 + spaces % 2;
+    format!("{}{}{}", " ".repeat(left), text, " ".repeat(spaces - left))
+}
+
+/// Cut text to a display width.
+fn fit(text: &str, width: usize) -> String {
+    let mut used = 0;
+    text.chars()
+        .take_while(|c| {
+            used += c.to_string().width();
+            used <= width
+        })
+        .collect()
+}
+
+/// Left-justify text in a width.
+fn left(text: &str, width: usize) -> String {
+    let text = fit(text, width);
+    let spaces = width - text.width();
+    format!("{}{}", text, " ".repeat(spaces))
+}
+
+/// Abbreviated weekday names from the week start, cut to the cell width.
+fn weekday_names(ctx: &CalContext, width: usize) -> Vec<String> {
+    let locale = get_system_locale();
+    // 2000-01-02 was a Sunday
+    let sunday = NaiveDate::from_ymd_opt(2000, 1, 2).unwrap();
+    (0..DAYS_IN_WEEK as u64)
+        .map(|i| {
+            let date = sunday + chrono::Days::new(i + ctx.week_start.num_days_from_sunday() as u64);
+            fit(&date.format_localized("%a", locale).to_string(), width)
+        })
+        .collect()
+}
+
+/// Week number right-aligned in a cell, highlighted with the week highlight color.
+fn week_cell(ctx: &CalContext, width: usize, week: u32, highlight: bool) -> String {
+    let sequence = &ctx.palette.weekhighlight;
+    if highlight && ctx.color && !sequence.is_empty() {
+        format!(
+            "{}{}{:2}{}",
+            " ".repeat(width - 2),
+            sequence,
+            week,
+            COLOR_RESET
+        )
+    } else {
+        format!("{:>width$}", week)
+    }
+}
+
+/// Number right-aligned in a cell, highlighted with the given color.
+fn cell(ctx: &CalContext, width: usize, number: u32, highlight: Option<&str>) -> String {
+    if let Some(sequence) = highlight.filter(|sequence| ctx.color && !sequence.is_empty()) {
+        let digits = if ctx.julian { 3 } else { 2 };
+        format!(
+            "{}{}{:>digits$}{}",
+            " ".repeat(width - digits),
+            sequence,
+            number,
+            COLOR_RESET
+        )
+    } else {
+        format!("{:>width$}", number)
+    }
+}
+
+/// Month title: "Month Year", or only the month in the year view or when split.
+fn titles(layout: &Layout, months: &[Month]) -> (Vec<String>, Option<Vec<String>>) {
+    let names: Vec<String> = months.iter().map(|m| get_month_name(m.month)).collect();
+    if layout.header_year {
+        (names, None)
+    } else if layout.header_hint {
+        let years = months.iter().map(|m| m.year.to_string()).collect();
+        (names, Some(years))
+    } else {
+        let titles = months
+            .iter()
+            .zip(names)
+            .map(|(m, name)| format!("{} {}", name, m.year))
+            .collect();
+        (titles, None)
+    }
+}
+
+/// Join month columns with the gutter between them.
+fn join(layout: &Layout, parts: impl IntoIterator<Item = String>) -> String {
+    parts
+        .into_iter()
+        .collect::<Vec<_>>()
+        .join(&" ".repeat(layout.gutter_width))
+}
+
+/// Horizontal row of months: header, weekday names and six week lines.
+fn output_months(ctx: &CalContext, layout: &Layout, months: &[Month]) -> Vec<String> {
+    let mut lines = Vec::new();
+    let (titles, years) = titles(layout, months);
+    lines.push(join(
+        layout,
+        titles.iter().map(|t| center(t, layout.week_width)),
+    ));
+    if let Some(years) = years {
+        lines.push(join(
+            layout,
+            years.iter().map(|y| center(y, layout.week_width)),
+        ));
+    }
+
+    let width = layout.day_width - 1;
+    let headings = weekday_names(ctx, width)
+        .iter()
+        .map(|name| center(name, width))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let headings = if ctx.week_numbers {
+        format!("{}{}", " ".repeat(WNUM_LEN), headings)
+    } else {
+        headings
+    };
+    lines.push(join(layout, months.iter().map(|_| headings.clone())));
+
+    for line in 0..WEEK_LINES {
+        lines.push(join(
+            layout,
+            months.iter().map(|m| {
+                let mut out = String::new();
+                // The first day is one char narrower, unless a week number precedes it
+                let mut skip = if ctx.week_numbers {
+                    match m.weeks[line] {
+                        Some(week) => {
+                            out.push_str(&week_cell(ctx, 2, week, m.highlight_line == Some(line)))
+                        }
+                        None => out.push_str("  "),
+                    }
+                    layout.day_width
+                } else {
+                    layout.day_width - 1
+                };
+                for d in line * DAYS_IN_WEEK..(line + 1) * DAYS_IN_WEEK {
+                    match m.days[d] {
+                        Some((day, number)) => out.push_str(&cell(
+                            ctx,
+                            skip,
+                            number,
+                            m.highlight_color(ctx, day, line),
+                        )),
+                        None => out.push_str(&" ".repeat(skip)),
+                    }
+                    skip = layout.day_width;
+                }
+                out
+            }),
+        ));
+    }
+    lines
+}
+
+/// Vertical row of months: weekdays down the left, weeks as columns.
+fn output_months_vertical(ctx: &CalContext, layout: &Layout, months: &[Month]) -> Vec<String> {
+    let mut lines = Vec::new();
+    let month_width = layout.day_width * WEEK_LINES;
+    let padding = " ".repeat(layout.day_width + 1);
+    let (titles, years) = titles(layout, months);
+    lines.push(format!(
+        "{}{}",
+        padding,
+        join(layout, titles.iter().map(|t| left(t, month_width)))
+    ));
+    if let Some(years) = years {
+        lines.push(format!(
+            "{}{}",
+            padding,
+            join(layout, years.iter().map(|y| left(y, month_width)))
+        ));
+    }
+
+    for (i, name) in weekday_names(ctx, layout.day_width - 1).iter().enumerate() {
+        let days = join(
+            layout,
+            months.iter().map(|m| {
+                (0..WEEK_LINES)
+                    .map(|week| match m.days[i + DAYS_IN_WEEK * week] {
+                        Some((day, number)) => cell(
+                            ctx,
+                            layout.day_width,
+                            number,
+                            m.highlight_color(ctx, day, week),
+                        ),
+                        None => " ".repeat(layout.day_width),
+                    })
+                    .collect::<String>()
+            }),
+        );
+        lines.push(format!("{}{}", left(name, layout.day_width - 1), days));
+    }
+
+    if ctx.week_numbers {
+        let weeks = join(
+            layout,
+            months.iter().map(|m| {
+                m.weeks
+                    .iter()
+                    .enumerate()
+                    .map(|(line, week)| match week {
+                        Some(week) => {
+                            week_cell(ctx, layout.day_width, *week, m.highlight_line == Some(line))
+                        }
+                        None => " ".repeat(layout.day_width),
+                    })
+                    .collect::<String>()
+            }),
+        );
+        lines.push(format!("{}{}", " ".repeat(layout.day_width - 1), weeks));
+    }
+    lines
+}
+
+/// Months from year/month on, `months_in_row` to a row.
+fn output(ctx: &CalContext, layout: &Layout, year: i32, month: u32, count: u32) -> Vec<String> {
+    let months: Vec<Month> = (0..count as i32)
+        .map(|i| {
+            let index = year * 12 + month as i32 - 1 + i;
+            Month::fill(ctx, index.div_euclid(12), index.rem_euclid(12) as u32 + 1)
+        })
+        .collect();
+    months
+        .chunks(layout.months_in_row)
+        .flat_map(|row| {
+            if ctx.vertical {
+                output_months_vertical(ctx, layout, row)
+            } else {
+                output_months(ctx, layout, row)
+            }
+        })
+        .collect()
+}
+
+/// Months per row: three, or fewer on a narrow terminal or with --columns.
+fn months_in_row(ctx: &CalContext, count: u32) -> usize {
+    ctx.months_per_row().clamp(1, count.max(1)) as usize
+}
+
+/// Lines for `count` months starting at year/month (-3 and -n use the narrow gutter,
+/// -Y the year gutter).
+pub fn format_months(
+    ctx: &CalContext,
+    year: i32,
+    month: u32,
+    count: u32,
+    wide_gutter: bool,
+) -> Vec<String> {
+    let gutter = if wide_gutter {
+        GUTTER_WIDTH_YEAR
+    } else {
+        GUTTER_WIDTH_REGULAR
+    };
+    let mut layout = Layout::new(ctx, year, gutter, false);
+    layout.months_in_row = months_in_row(ctx, count);
+    output(ctx, &layout, year, month, count)
+}
+
+/// Lines for a whole year: the centered year, a blank line and twelve months.
+pub fn format_year(ctx: &CalContext, year: i32) -> Vec<String> {
+    let mut layout = Layout::new(ctx, year, GUTTER_WIDTH_YEAR, true);
+    layout.months_in_row = months_in_row(ctx, 12);
+    let width =
+        layout.months_in_row * layout.week_width + (layout.months_in_row - 1) * layout.gutter_width;
+    let mut lines = vec![center(&year.to_string(), width), String::new()];
+    lines.extend(output(ctx, &layout, year, 1, 12));
+    lines
+}
+
+/// Print lines of a compat layout.
+pub fn print_lines(out: &mut impl Write, lines: &[String]) -> io::Result<()> {
+    for line in lines {
+        writeln!(out, "{}", line)?;
+    }
+    Ok(())
+}
+
+/// First month shown by -3 or --span -n: half the months (rounded down) come before.
+pub fn span_start(year: i32, month: u32, count: u32) -> (i32, u32) {
+    let index = year * 12 + month as i32 - 1 - count as i32 / 2;
+    (index.div_euclid(12), index.rem_euclid(12) as u32 + 1)
+}
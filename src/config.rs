@@ -0,0 +1,327 @@
+//! User configuration file (~/.config/cal/config.toml) with named profiles.
+
+use std::ffi::OsString;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use clap::parser::ValueSource;
+use clap::{ArgAction, ArgMatches, Command};
+use toml::{Table, Value};
+
+/// Options that only make sense on the command line.
+const CLI_ONLY: &[&str] = &[
+    "help",
+    "version",
+    "profile",
+    "print-config",
+    "ncal",
+    "easter",
+    "reforms",
+    "today",
+];
+
+/// Options that override each other: any of them on the command line drops the rest from the config.
+const EXCLUSIVE: &[&[&str]] = &[
+    &["sunday", "monday"],
+    &["reform", "iso"],
+    &["year", "twelve", "three", "months", "one"],
+];
+
+/// Options set by environment variables, which take precedence over the config file.
+const ENVIRONMENT: &[(&str, &[&str])] = &[
+    ("color", &["NO_COLOR", "CLICOLOR_FORCE"]),
+    ("compat", &["POSIXLY_CORRECT"]),
+];
+
+/// Settings read from the config file.
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    /// File the settings were read from.
+    pub path: Option<PathBuf>,
+    /// Selected profile.
+    pub profile: Option<String>,
+    /// Settings as (long option name, value), profile entries override top-level ones.
+    pub settings: Vec<(String, Value)>,
+    /// Options actually taken from the config (not overridden on the command line).
+    pub applied: Vec<String>,
+    /// Options left to the environment as (option, variable).
+    pub ignored: Vec<(String, String)>,
+}
+
+/// Config file location: CAL_CONFIG, else $XDG_CONFIG_HOME/cal/config.toml or ~/.config/cal/config.toml.
+pub fn config_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os("CAL_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+    std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+        .map(|config| config.join("cal").join("config.toml"))
+}
+
+impl Config {
+    /// Read the config file (a missing file gives no settings) and select a profile.
+    pub fn load(path: Option<&Path>, profile: Option<&str>) -> Result<Self, String> {
+        let (text, path) = match path.map(|path| (path, std::fs::read_to_string(path))) {
+            Some((path, Ok(text))) => (text, Some(path)),
+            Some((path, Err(e))) if e.kind() != std::io::ErrorKind::NotFound => {
+                return Err(format!("Cannot read config {}: {}", path.display(), e));
+            }
+            _ => (String::new(), None),
+        };
+        let mut config = Config::parse(&text, profile).map_err(|e| in_file(e, path))?;
+        config.path = path.map(Path::to_path_buf);
+        Ok(config)
+    }
+
+    /// Parse config text: top-level settings overlaid with the [profiles.NAME] table.
+    pub fn parse(text: &str, profile: Option<&str>) -> Result<Self, String> {
+        let mut table: Table = text
+            .parse()
+            .map_err(|e: toml::de::Error| format!("Invalid config: {}", e.message()))?;
+        let profiles = match table.remove("profiles") {
+            Some(Value::Table(profiles)) => profiles,
+            None => Table::new(),
+            Some(_) => return Err("Invalid config: 'profiles' must be a table".to_string()),
+        };
+
+        let mut settings: Vec<(String, Value)> = table
+            .into_iter()
+            .map(|(key, value)| (key.replace('_', "-"), value))
+            .collect();
+        if let Some(name) = profile {
+            let Some(Value::Table(entries)) = profiles.get(name) else {
+                let names: Vec<&str> = profiles.keys().map(String::as_str).collect();
+                return Err(format!(
+                    "Unknown profile: {} (available: {})",
+                    name,
+                    if names.is_empty() {
+                        "none".to_string()
+                    } else {
+                        names.join(", ")
+                    }
+                ));
+            };
+            for (key, value) in entries {
+                let key = key.replace('_', "-");
+                settings.retain(|(name, _)| *name != key);
+                settings.push((key, value.clone()));
+            }
+        }
+
+        Ok(Config {
+            path: None,
+            profile: profile.map(str::to_string),
+            settings,
+            applied: Vec::new(),
+            ignored: Vec::new(),
+        })
+    }
+
+    /// Apply the settings not given on the command line or by the environment as
+    /// argument defaults, so that clap reports them as defaults underneath the matches.
+    pub fn apply(&mut self, mut cmd: Command, cli: &ArgMatches) -> Result<Command, String> {
+        let given = |cmd: &Command, long: &str| {
+            cmd.get_arguments()
+                .find(|arg| arg.get_long() == Some(long))
+                .is_some_and(|arg| {
+                    cli.value_source(arg.get_id().as_str()) == Some(ValueSource::CommandLine)
+                })
+        };
+
+        for (key, value) in &self.settings {
+            // week-start = "monday" | "sunday" is a friendlier spelling of the two flags
+            let (key, value) = match (key.as_str(), value) {
+                ("week-start", Value::String(day))
+                    if matches!(day.as_str(), "monday" | "sunday") =>
+                {
+                    (day.as_str(), &Value::Boolean(true))
+                }
+                ("week-start", _) => {
+                    return Err(format!(
+                        "Invalid config value for week-start: {} (expected \"monday\" or \"sunday\")",
+                        value
+                    ));
+                }
+                _ => (key.as_str(), value),
+            };
+            let arg = cmd
+                .get_arguments()
+                .find(|arg| arg.get_long() == Some(key) && !CLI_ONLY.contains(&key))
+                .ok_or_else(|| format!("Unknown config option: {}", key))?;
+
+            let invalid = |expected: &str| {
+                format!(
+                    "Invalid config value for {}: {} (expected {})",
+                    key, value, expected
+                )
+            };
+            let missing = optional_value(&cmd, key);
+            let defaults: Vec<String> = match value {
+                _ if matches!(arg.get_action(), ArgAction::SetTrue) => match value {
+                    Value::Boolean(true) => vec!["true".to_string()],
+                    Value::Boolean(false) => continue,
+                    _ => return Err(invalid("true or false")),
+                },
+                // An option with an optional value, switched on as if given without one
+                Value::Boolean(true) if missing.is_some() => missing.unwrap_or_default(),
+                Value::Boolean(false) if missing.is_some() => continue,
+                Value::Array(items) => items
+                    .iter()
+                    .map(|item| scalar(item).ok_or_else(|| invalid("a string or number")))
+                    .collect::<Result<_, _>>()?,
+                _ => vec![scalar(value).ok_or_else(|| invalid("a string or number"))?],
+            };
+
+            let group = EXCLUSIVE
+                .iter()
+                .find(|group| group.contains(&key))
+                .copied()
+                .unwrap_or(std::slice::from_ref(&key));
+            if group.iter().any(|long| given(&cmd, long)) {
+                continue;
+            }
+            if let Some((_, vars)) = ENVIRONMENT.iter().find(|(long, _)| *long == key)
+                && let Some(var) = vars
+                    .iter()
+                    .find(|var| std::env::var_os(var).is_some_and(|value| !value.is_empty()))
+            {
+                self.ignored.push((key.to_string(), var.to_string()));
+                continue;
+            }
+
+            let id = arg.get_id().clone();
+            cmd = cmd.mut_arg(id, |arg| arg.default_values(defaults));
+            self.applied.push(key.to_string());
+        }
+        Ok(cmd)
+    }
+
+    /// Effective settings as (option, value, source) for --print-config.
+    pub fn effective(
+        &self,
+        cmd: &Command,
+        matches: &ArgMatches,
+    ) -> Vec<(String, Value, &'static str)> {
+        cmd.get_arguments()
+            .filter_map(|arg| {
+                let long = arg.get_long()?;
+                if CLI_ONLY.contains(&long) {
+                    return None;
+                }
+                let id = arg.get_id().as_str();
+                let value = if matches!(arg.get_action(), ArgAction::SetTrue) {
+                    Value::Boolean(matches.get_flag(id))
+                } else {
+                    let mut raw: Vec<Value> = matches
+                        .get_raw(id)?
+                        .map(|value| {
+                            let text = value.to_string_lossy();
+                            text.parse::<i64>()
+                                .map(Value::Integer)
+                                .unwrap_or_else(|_| Value::String(text.into_owned()))
+                        })
+                        .collect();
+                    match arg.get_action() {
+                        ArgAction::Append => Value::Array(raw),
+                        // Optional-value option given without a value
+                        _ if raw.is_empty() => Value::Boolean(true),
+                        _ => raw.pop()?,
+                    }
+                };
+                let source = if self.applied.iter().any(|key| key == long) {
+                    "config"
+                } else if matches.value_source(id) == Some(ValueSource::CommandLine) {
+                    "command line"
+                } else {
+                    "default"
+                };
+                Some((long.to_string(), value, source))
+            })
+            .collect()
+    }
+}
+
+/// Resolve the config file underneath the command line (config < environment < command line).
+///
+/// The profile comes from --profile or CAL_PROFILE. Returns the command with the config
+/// settings as argument defaults, and the config.
+pub fn merge(argv: &[OsString], cmd: Command) -> Result<(Command, Config), String> {
+    // Invalid command lines (and --help) are reported by the real parse
+    let Ok(cli) = cmd.clone().try_get_matches_from(argv) else {
+        return Ok((cmd, Config::default()));
+    };
+    let profile = cli
+        .get_one::<String>("profile")
+        .cloned()
+        .or_else(|| std::env::var("CAL_PROFILE").ok().filter(|p| !p.is_empty()));
+
+    let mut config = Config::load(config_path().as_deref(), profile.as_deref())?;
+    let cmd = config
+        .apply(cmd, &cli)
+        .map_err(|e| in_file(e, config.path.as_deref()))?;
+    Ok((cmd, config))
+}
+
+/// Values an option with an optional value takes when given without one, if any.
+fn optional_value(cmd: &Command, long: &str) -> Option<Vec<String>> {
+    let arg = cmd
+        .get_arguments()
+        .find(|arg| arg.get_long() == Some(long))?;
+    if arg.get_num_args()?.min_values() != 0 {
+        return None;
+    }
+    let matches = cmd
+        .clone()
+        .try_get_matches_from(["cal".to_string(), format!("--{}", long)])
+        .ok()?;
+    let values: Vec<String> = matches
+        .get_raw(arg.get_id().as_str())?
+        .map(|value| value.to_string_lossy().into_owned())
+        .collect();
+    (!values.is_empty()).then_some(values)
+}
+
+/// Config value of a single-valued option as text.
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Integer(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Name the config file in an error message.
+fn in_file(error: String, path: Option<&Path>) -> String {
+    match path {
+        Some(path) => format!("{} (in {})", error, path.display()),
+        None => error,
+    }
+}
+
+/// Print the effective settings as TOML with their source.
+pub fn print_config(
+    out: &mut impl Write,
+    config: &Config,
+    cmd: &Command,
+    matches: &ArgMatches,
+) -> io::Result<()> {
+    match (&config.path, &config.profile) {
+        (Some(path), Some(profile)) => writeln!(out, "# {} (profile {})", path.display(), profile)?,
+        (Some(path), None) => writeln!(out, "# {}", path.display())?,
+        (None, _) => writeln!(out, "# no config file")?,
+    }
+    let lines: Vec<(String, &str)> = config
+        .effective(cmd, matches)
+        .into_iter()
+        .map(|(key, value, source)| (format!("{} = {}", key, value), source))
+        .collect();
+    let width = lines.iter().map(|(line, _)| line.len()).max().unwrap_or(0);
+    for (line, source) in lines {
+        writeln!(out, "{:<width$}  # {}", line, source, width = width)?;
+    }
+    for (key, var) in &config.ignored {
+        writeln!(out, "# {} from the config is overridden by {}", key, var)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,131 @@
+//! Diagnostics for invalid arguments: error class, message, hint and exit code.
+
+use std::fmt;
+use std::io;
+
+/// Class of error; each class exits with its own code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// Runtime failure: files, plugins, conflicting options.
+    Other,
+    /// Argument that matches no date form.
+    Argument,
+    /// Unknown month name or number.
+    Month,
+    /// Day outside the month.
+    Day,
+    /// Year outside 1-9999 or not a number.
+    Year,
+    /// Input that could mean more than one thing.
+    Ambiguous,
+    /// Unreadable or invalid config file.
+    Config,
+    /// Output could not be written (closed pipe, full disk).
+    Output(io::ErrorKind),
+}
+
+impl ErrorKind {
+    /// Process exit code for this class; 2 is left to clap's usage errors.
+    pub fn exit_code(self) -> i32 {
+        match self {
+            ErrorKind::Other | ErrorKind::Output(_) => 1,
+            ErrorKind::Argument => 3,
+            ErrorKind::Month => 4,
+            ErrorKind::Day => 5,
+            ErrorKind::Year => 6,
+            ErrorKind::Ambiguous => 7,
+            ErrorKind::Config => 8,
+        }
+    }
+}
+
+/// Error with a class and an optional hint ("did you mean september?").
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalError {
+    pub kind: ErrorKind,
+    pub message: String,
+    pub hint: Option<String>,
+}
+
+impl CalError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        CalError {
+            kind,
+            message: message.into(),
+            hint: None,
+        }
+    }
+
+    /// Attach a hint, if any.
+    pub fn with_hint(mut self, hint: Option<String>) -> Self {
+        self.hint = hint;
+        self
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        self.kind.exit_code()
+    }
+}
+
+impl fmt::Display for CalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for CalError {}
+
+impl From<String> for CalError {
+    fn from(message: String) -> Self {
+        CalError::new(ErrorKind::Other, message)
+    }
+}
+
+impl From<io::Error> for CalError {
+    fn from(error: io::Error) -> Self {
+        CalError::new(ErrorKind::Output(error.kind()), error.to_string())
+    }
+}
+
+impl From<&str> for CalError {
+    fn from(message: &str) -> Self {
+        CalError::new(ErrorKind::Other, message)
+    }
+}
+
+/// Closest candidate within a few typos (about one per three letters), if any.
+pub fn closest<'a>(word: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let word = word.to_lowercase();
+    let limit = (word.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(&word, &candidate.to_lowercase()), candidate))
+        .filter(|&(distance, _)| distance <= limit)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Optimal string alignment distance: insertions, deletions, substitutions and
+/// transpositions of adjacent letters each count as one edit.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    rows[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut best = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = best;
+        }
+    }
+    rows[a.len()][b.len()]
+}
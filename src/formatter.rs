@@ -1,12 +1,18 @@
 //! Calendar formatting and display with localization and color support.
 
-use chrono::{Datelike, Locale, NaiveDate, Weekday};
+use chrono::{DateTime, Datelike, FixedOffset, Local, Locale, NaiveDate, Utc, Weekday};
 use unicode_width::UnicodeWidthStr;
 
+use crate::astro::{Daylight, Season, season_instant, sun_times};
+use crate::error::{CalError, closest};
+use crate::project::EventKind;
+use crate::query::{Match, Query};
 use crate::types::{
-    COLOR_RED, COLOR_RESET, COLOR_REVERSE, COLOR_SAND_YELLOW, COLOR_TEAL, CalContext,
-    GUTTER_WIDTH_YEAR, MonthData,
+    COLOR_RESET, COLOR_TEAL, CalContext, GUTTER_WIDTH_YEAR, LayoutMatch, MONTH_WIDTH_VERTICAL,
+    MonthData, Period, WeekType,
 };
+use crate::zoneinfo::OffsetChange;
+use std::io::{self, Write};
 
 #[cfg(feature = "plugins")]
 use std::sync::Mutex;
@@ -39,7 +45,7 @@ pub fn preload_year_holidays(ctx: &CalContext, year: i32) {
         }
     }
 
-    if !init_plugin() {
+    if !init_plugin(ctx) {
         return;
     }
 
@@ -58,14 +64,17 @@ pub fn preload_year_holidays(ctx: &CalContext, year: i32) {
 pub fn preload_year_holidays(_ctx: &CalContext, _year: i32) {}
 
 #[cfg(feature = "plugins")]
-fn init_plugin() -> bool {
+fn init_plugin(ctx: &CalContext) -> bool {
     let mut plugin_guard = PLUGIN.lock().unwrap();
     if plugin_guard.is_some() {
         return true;
     }
 
     if let Some(plugin) = crate::plugin_api::try_load_plugin() {
-        let country = plugin.get_country_from_locale();
+        let country = ctx
+            .country
+            .clone()
+            .unwrap_or_else(|| plugin.get_country_from_locale());
         let mut country_guard = COUNTRY.lock().unwrap();
         *country_guard = Some(country.clone());
         *plugin_guard = Some(plugin);
@@ -75,8 +84,15 @@ fn init_plugin() -> bool {
     }
 }
 
+/// Check if the holiday plugin can be loaded.
 #[cfg(feature = "plugins")]
-fn get_holiday_code(ctx: &CalContext, year: i32, month: u32, day: u32) -> i32 {
+pub(crate) fn plugin_available(ctx: &CalContext) -> bool {
+    init_plugin(ctx)
+}
+
+/// Get the plugin day code: 0 working day, 1 weekend, 2 shortened, 8 public holiday.
+#[cfg(feature = "plugins")]
+pub(crate) fn get_holiday_code(ctx: &CalContext, year: i32, month: u32, day: u32) -> i32 {
     if !ctx.holidays {
         return 0;
     }
@@ -113,7 +129,7 @@ fn get_holiday_code(ctx: &CalContext, year: i32, month: u32, day: u32) -> i32 {
     }
 
     // Cache miss - fetch data for the month
-    if !init_plugin() {
+    if !init_plugin(ctx) {
         return 0;
     }
 
@@ -148,7 +164,7 @@ fn get_holiday_code(ctx: &CalContext, year: i32, month: u32, day: u32) -> i32 {
 }
 
 #[cfg(not(feature = "plugins"))]
-fn get_holiday_code(_ctx: &CalContext, _year: i32, _month: u32, _day: u32) -> i32 {
+pub(crate) fn get_holiday_code(_ctx: &CalContext, _year: i32, _month: u32, _day: u32) -> i32 {
     0
 }
 
@@ -190,10 +206,67 @@ pub fn get_system_locale() -> Locale {
         .unwrap_or(Locale::en_US)
 }
 
+/// Week start and numbering from the LC_ALL, LC_TIME or LANG locale.
+///
+/// C, POSIX, unset and unknown locales keep Monday and ISO weeks.
+pub fn locale_week_defaults() -> (Weekday, WeekType) {
+    locale_week().unwrap_or((Weekday::Mon, WeekType::Iso))
+}
+
+/// Week start and numbering of the LC_ALL, LC_TIME or LANG locale, if one is set and known.
+pub fn locale_week() -> Option<(Weekday, WeekType)> {
+    ["LC_ALL", "LC_TIME", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|value| locale_week_for(&value))
+}
+
+/// Week start and numbering of a locale name such as `en_US.UTF-8`, if it is known.
+pub fn locale_week_for(name: &str) -> Option<(Weekday, WeekType)> {
+    name.split(['.', '@'])
+        .next()?
+        .parse::<Locale>()
+        .ok()
+        .filter(|&locale| locale != Locale::POSIX)
+        .map(week_defaults_in)
+}
+
+/// Week start and numbering of a locale, like glibc `_NL_TIME_FIRST_WEEKDAY` and `week`.
+///
+/// US numbering is used for Sunday-first locales whose first week contains January 1.
+pub fn week_defaults_in(locale: Locale) -> (Weekday, WeekType) {
+    let week = pure_rust_locales::locale_match!(locale => LC_TIME::WEEK);
+    let first_weekday = pure_rust_locales::locale_match!(locale => LC_TIME::FIRST_WEEKDAY);
+
+    // first_weekday counts from 1 at the week base date (19971130, a Sunday)
+    let base = week
+        .and_then(|week| week.get(1))
+        .and_then(|&date| {
+            NaiveDate::from_ymd_opt(
+                (date / 10000) as i32,
+                (date / 100 % 100) as u32,
+                (date % 100) as u32,
+            )
+        })
+        .map_or(Weekday::Sun, |date| date.weekday());
+    let start = (1..first_weekday.unwrap_or(1)).fold(base, |day, _| day.succ());
+    let first_week = week.and_then(|week| week.get(2)).copied().unwrap_or(4);
+    let week_type = if start == Weekday::Sun && first_week == 1 {
+        WeekType::Us
+    } else {
+        WeekType::Iso
+    };
+    (start, week_type)
+}
+
 /// Get month name in nominative case for current locale.
 pub fn get_month_name(month: u32) -> String {
-    let locale = get_system_locale();
+    get_month_name_in(month, get_system_locale())
+}
 
+/// Get month name in nominative case for a locale.
+pub fn get_month_name_in(month: u32, locale: Locale) -> String {
     match locale {
         Locale::ru_RU => [
             "Январь",
@@ -247,59 +320,86 @@ pub fn get_month_name(month: u32) -> String {
     }
 }
 
-/// Parse month from string (numeric 1-12 or name in English/Russian).
+/// Parse month from string: numeric 1-12, or a full, abbreviated or genitive name in the
+/// current locale, English or Russian (case- and accent-insensitive).
 pub fn parse_month(s: &str) -> Option<u32> {
+    parse_month_in(s, get_system_locale())
+}
+
+/// Parse month from string with names of the given locale, English and Russian.
+pub fn parse_month_in(s: &str, locale: Locale) -> Option<u32> {
     if let Ok(n) = s.parse::<u32>()
         && (1..=12).contains(&n)
     {
         return Some(n);
     }
 
-    let s_lower = s.to_lowercase();
-    let month_names: [(&str, u32); 35] = [
-        // English full names
-        ("january", 1),
-        ("february", 2),
-        ("march", 3),
-        ("april", 4),
-        ("may", 5),
-        ("june", 6),
-        ("july", 7),
-        ("august", 8),
-        ("september", 9),
-        ("october", 10),
-        ("november", 11),
-        ("december", 12),
-        // Russian full names
-        ("январь", 1),
-        ("февраль", 2),
-        ("март", 3),
-        ("апрель", 4),
-        ("май", 5),
-        ("июнь", 6),
-        ("июль", 7),
-        ("август", 8),
-        ("сентябрь", 9),
-        ("октябрь", 10),
-        ("ноябрь", 11),
-        ("декабрь", 12),
-        // English short forms
-        ("jan", 1),
-        ("feb", 2),
-        ("mar", 3),
-        ("apr", 4),
-        ("jun", 6),
-        ("jul", 7),
-        ("aug", 8),
-        ("sep", 9),
-        ("oct", 10),
-        ("nov", 11),
-        ("dec", 12),
-    ];
-    month_names
-        .iter()
-        .find(|(name, _)| *name == s_lower)
-        .map(|(_, num)| *num)
+    let wanted = fold_month_name(s);
+    if wanted.is_empty() {
+        return None;
+    }
+    [locale, Locale::en_US, Locale::ru_RU]
+        .into_iter()
+        .find_map(|locale| {
+            (1..=12).find(|&month| {
+                month_name_forms(month, locale)
+                    .iter()
+                    .any(|form| fold_month_name(form) == wanted)
+            })
+        })
+}
+
+/// Closest month name to a misspelt one ("setpember" gives "september").
+pub fn suggest_month(s: &str) -> Option<String> {
+    suggest_month_in(s, get_system_locale())
+}
+
+/// Closest month name in the given locale, English or Russian.
+pub fn suggest_month_in(s: &str, locale: Locale) -> Option<String> {
+    let names: Vec<String> = [locale, Locale::en_US, Locale::ru_RU]
+        .into_iter()
+        .flat_map(|locale| (1..=12).flat_map(move |month| month_name_forms(month, locale)))
+        .map(|name| name.to_lowercase())
+        .collect();
+    closest(&fold_month_name(s), names.iter().map(String::as_str)).map(str::to_string)
+}
+
+/// Month name forms accepted when parsing: nominative, formatted (genitive in many
+/// locales) and abbreviated.
+fn month_name_forms(month: u32, locale: Locale) -> [String; 3] {
+    let date = NaiveDate::from_ymd_opt(2000, month, 1).unwrap();
+    [
+        get_month_name_in(month, locale),
+        date.format_localized("%B", locale).to_string(),
+        date.format_localized("%b", locale).to_string(),
+    ]
+}
+
+/// Normalize a month name for comparison: lowercase, no accents, no trailing dot.
+fn fold_month_name(s: &str) -> String {
+    s.trim()
+        .trim_end_matches('.')
+        .to_lowercase()
+        .chars()
+        .map(|c| match c {
+            'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => 'a',
+            'ç' | 'ć' | 'č' => 'c',
+            'ď' => 'd',
+            'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ė' | 'ę' | 'ě' => 'e',
+            'ì' | 'í' | 'î' | 'ï' | 'ī' | 'į' => 'i',
+            'ł' | 'ľ' | 'ĺ' => 'l',
+            'ñ' | 'ń' | 'ň' => 'n',
+            'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ő' => 'o',
+            'ŕ' | 'ř' => 'r',
+            'ś' | 'š' | 'ş' => 's',
+            'ť' | 'ţ' => 't',
+            'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' | 'ű' | 'ų' => 'u',
+            'ý' | 'ÿ' => 'y',
+            'ź' | 'ż' | 'ž' => 'z',
+            'ё' => 'е',
+            _ => c,
+        })
+        .collect()
 }
 
 /// Format month header with optional year and color.
@@ -309,6 +409,17 @@ pub fn format_month_header(
     width: usize,
     show_year: bool,
     color: bool,
+) -> String {
+    format_month_header_in(year, month, width, show_year, color.then_some(COLOR_TEAL))
+}
+
+/// Format month header with optional year in the given color sequence.
+pub fn format_month_header_in(
+    year: i32,
+    month: u32,
+    width: usize,
+    show_year: bool,
+    color: Option<&str>,
 ) -> String {
     let month_name = get_month_name(month);
     let header = if show_year {
@@ -316,12 +427,28 @@ pub fn format_month_header(
     } else {
         month_name
     };
-    let centered = center_text(&header, width);
-    if color {
-        format!("{}{}{}", COLOR_TEAL, centered, COLOR_RESET)
-    } else {
-        centered
+    paint(color, &center_text(&header, width))
+}
+
+/// Wrap text in a color sequence; empty or missing sequences leave it plain.
+fn paint(color: Option<&str>, text: &str) -> String {
+    match color {
+        Some(color) if !color.is_empty() => format!("{}{}{}", color, text, COLOR_RESET),
+        _ => text.to_string(),
+    }
+}
+
+/// Display width of text with SGR color sequences left out.
+fn visible_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut rest = text;
+    while let Some(start) = rest.find('\x1b') {
+        width += rest[..start].width();
+        rest = rest[start..]
+            .find('m')
+            .map_or("", |end| &rest[start + end + 1..]);
     }
+    width + rest.width()
 }
 
 /// Center text within a specified width, accounting for Unicode character widths.
@@ -343,27 +470,12 @@ fn center_text(text: &str, width: usize) -> String {
 
 /// Get weekday order based on week start day.
 pub fn get_weekday_order(week_start: Weekday) -> [Weekday; 7] {
-    match week_start {
-        Weekday::Mon => [
-            Weekday::Mon,
-            Weekday::Tue,
-            Weekday::Wed,
-            Weekday::Thu,
-            Weekday::Fri,
-            Weekday::Sat,
-            Weekday::Sun,
-        ],
-        Weekday::Sun => [
-            Weekday::Sun,
-            Weekday::Mon,
-            Weekday::Tue,
-            Weekday::Wed,
-            Weekday::Thu,
-            Weekday::Fri,
-            Weekday::Sat,
-        ],
-        _ => unreachable!(),
-    }
+    let mut day = week_start;
+    std::array::from_fn(|_| {
+        let current = day;
+        day = day.succ();
+        current
+    })
 }
 
 /// Get 2-character weekday abbreviation for current locale.
@@ -375,6 +487,14 @@ pub fn get_weekday_short_name(weekday: Weekday, locale: Locale) -> String {
     day_name.chars().take(2).collect()
 }
 
+/// Get full weekday name for locale.
+pub fn get_weekday_name(weekday: Weekday, locale: Locale) -> String {
+    let base_date = NaiveDate::from_ymd_opt(2000, 1, 3).unwrap();
+    let offset = weekday.num_days_from_monday() as i64;
+    let date = base_date + chrono::Duration::days(offset);
+    date.format_localized("%A", locale).to_string()
+}
+
 /// Format weekday header row with optional week numbers and color.
 pub fn format_weekday_headers(ctx: &CalContext, week_numbers: bool) -> String {
     let locale = get_system_locale();
@@ -390,8 +510,9 @@ pub fn format_weekday_headers(ctx: &CalContext, week_numbers: bool) -> String {
 
     let weekday_order = get_weekday_order(ctx.week_start);
 
-    if ctx.color {
-        result.push_str(COLOR_SAND_YELLOW);
+    let colored = ctx.color && !ctx.palette.weekdays.is_empty();
+    if colored {
+        result.push_str(&ctx.palette.weekdays);
     }
 
     for (i, &weekday) in weekday_order.iter().enumerate() {
@@ -410,72 +531,231 @@ pub fn format_weekday_headers(ctx: &CalContext, week_numbers: bool) -> String {
         }
     }
 
-    if ctx.color {
+    if colored {
         result.push_str(COLOR_RESET);
     }
 
     result
 }
 
-/// Format day cell with color highlighting.
+/// Pick highlight color for a day cell.
+///
+/// Color priority: selected day > today > highlighted week > today in another zone > marked event >
+/// project release/holiday/freeze > shortened day > weekend/holiday > regular
+fn day_color(ctx: &CalContext, year: i32, month: u32, day: u32, weekday: Weekday) -> Option<&str> {
+    if !ctx.color {
+        return None;
+    }
+    let palette = &ctx.palette;
+    let color = if ctx.selected == Some((year, month, day)) {
+        &palette.selected
+    } else if ctx.today.day() == day && ctx.today.month() == month && ctx.today.year() == year {
+        &palette.today
+    } else if in_highlighted_week(ctx, year, month, day, weekday) {
+        &palette.weekhighlight
+    } else if ctx
+        .zone_todays
+        .iter()
+        .any(|(_, date)| date.day() == day && date.month() == month && date.year() == year)
+    {
+        &palette.othertoday
+    } else if is_marked_day(ctx, year, month, day) {
+        &palette.marked
+    } else if let Some(kind) = project_event(ctx, year, month, day) {
+        match kind {
+            EventKind::Release => &palette.release,
+            EventKind::Holiday => &palette.holiday,
+            EventKind::Freeze => &palette.freeze,
+        }
+    } else {
+        match get_holiday_code(ctx, year, month, day) {
+            2 => &palette.shortened,
+            8 => &palette.holiday,
+            1 => &palette.weekend,
+            _ if ctx.is_weekend(weekday) => &palette.weekend,
+            _ => &palette.workday,
+        }
+    };
+    (!color.is_empty()).then_some(color)
+}
+
+/// Whether the day lies in the week row highlighted with -w.
 ///
-/// Color priority: today > shortened day > weekend/holiday > regular
-fn format_day(
+/// Rows are numbered by their first day, so the whole row is highlighted even when
+/// the week numbering starts on another weekday.
+fn in_highlighted_week(
     ctx: &CalContext,
-    day: u32,
-    month: u32,
     year: i32,
+    month: u32,
+    day: u32,
     weekday: Weekday,
-    is_last: bool,
-) -> String {
-    let is_today = ctx.color
-        && ctx.today.day() == day
-        && ctx.today.month() == month
-        && ctx.today.year() == year;
-
-    let is_weekend = ctx.color && ctx.is_weekend(weekday);
-    let holiday_code = if ctx.color {
-        get_holiday_code(ctx, year, month, day)
-    } else {
-        0
+) -> bool {
+    let Some(week) = ctx.highlight_week else {
+        return false;
     };
-    let day_str = format!("{:>2}", day);
+    let offset = weekday.days_since(ctx.week_start);
+    let first = day.saturating_sub(offset).max(1);
+    NaiveDate::from_ymd_opt(year, month, first).is_some_and(|date| ctx.week_of(date) == week)
+}
 
-    let formatted = if is_today {
-        format!("{}{}{}", COLOR_REVERSE, day_str, COLOR_RESET)
-    } else if holiday_code == 2 {
-        format!("{}{}{}", COLOR_TEAL, day_str, COLOR_RESET)
-    } else if is_weekend || holiday_code == 1 || holiday_code == 8 {
-        format!("{}{}{}", COLOR_RED, day_str, COLOR_RESET)
+/// Color of the week number of the row starting at cell `start`.
+fn week_number_color<'a>(ctx: &'a CalContext, month: &MonthData, start: usize) -> Option<&'a str> {
+    let highlighted = (start..(start + 7).min(month.days.len()))
+        .find_map(|idx| month.days[idx].zip(month.weekdays[idx]))
+        .is_some_and(|(day, weekday)| {
+            in_highlighted_week(ctx, month.year, month.month, day, weekday)
+        });
+    let palette = &ctx.palette;
+    ctx.color.then_some(if highlighted {
+        palette.weekhighlight.as_str()
     } else {
-        day_str
+        palette.weeknumber.as_str()
+    })
+}
+
+/// Project event falling on the day.
+fn project_event(ctx: &CalContext, year: i32, month: u32, day: u32) -> Option<EventKind> {
+    let project = ctx.project.get()?;
+    project.event_on(ctx.to_gregorian(year, month, day)?)
+}
+
+/// Format project events and sprints overlapping an inclusive range of Gregorian dates.
+fn project_notes(ctx: &CalContext, first: NaiveDate, last: NaiveDate) -> Vec<String> {
+    let Some(project) = ctx.project.get() else {
+        return Vec::new();
+    };
+    let range = |start: NaiveDate, end: NaiveDate| {
+        if start == end {
+            start.format("%Y-%m-%d").to_string()
+        } else {
+            format!("{} .. {}", start.format("%Y-%m-%d"), end.format("%Y-%m-%d"))
+        }
     };
+    let mut notes: Vec<String> = project
+        .events_between(first, last)
+        .into_iter()
+        .map(|event| format!("{:<18} {}", event.name, range(event.start, event.end)))
+        .collect();
+    if let Some(sprints) = &project.sprints {
+        notes.extend(project.sprints_between(first, last).into_iter().map(
+            |(number, start, end)| {
+                format!(
+                    "{:<18} {}",
+                    format!("{} {}", sprints.name, number),
+                    range(start, end)
+                )
+            },
+        ));
+    }
+    notes
+}
 
-    if is_last {
-        formatted
-    } else {
-        format!("{} ", formatted)
+/// Check if a search match, equinox, solstice, offset change, polar day or polar night
+/// falls on the day.
+fn is_marked_day(ctx: &CalContext, year: i32, month: u32, day: u32) -> bool {
+    if ctx
+        .find
+        .as_ref()
+        .is_some_and(|query| query.matches(ctx, year, month, day))
+    {
+        return true;
+    }
+    if ctx.dst && !offset_changes_between(ctx, (year, month, day), (year, month, day)).is_empty() {
+        return true;
+    }
+    if ctx.seasons
+        && season_in_month(ctx, year, month).is_some_and(|(_, _, date)| date == (year, month, day))
+    {
+        return true;
+    }
+    if ctx.polar
+        && let Some(location) = &ctx.location
+    {
+        return sun_times(ctx.julian_day_number(year, month, day), location).is_polar();
+    }
+    false
+}
+
+/// Equinox or solstice as (season, local instant, date in the active calendar).
+type SeasonEvent = (Season, DateTime<FixedOffset>, (i32, u32, u32));
+
+/// Convert an instant to the display time zone (first `--tz`, or the system zone).
+fn to_display_zone(ctx: &CalContext, instant: DateTime<Utc>) -> DateTime<FixedOffset> {
+    match ctx.time_zones.first() {
+        Some(zone) => zone.to_local(instant),
+        None => instant.with_timezone(&Local).fixed_offset(),
+    }
+}
+
+/// Find UTC offset changes (zone name, change) with local dates in an inclusive range.
+fn offset_changes_between(
+    ctx: &CalContext,
+    first: (i32, u32, u32),
+    last: (i32, u32, u32),
+) -> Vec<(&str, OffsetChange)> {
+    const UNIX_EPOCH_JDN: i64 = 2_440_588;
+    const MARGIN: i64 = 14 * 3600;
+
+    let start = (ctx.julian_day_number(first.0, first.1, first.2) - UNIX_EPOCH_JDN) * 86400;
+    let end = (ctx.julian_day_number(last.0, last.1, last.2) + 1 - UNIX_EPOCH_JDN) * 86400;
+    let mut changes = Vec::new();
+    for zone in &ctx.time_zones {
+        for change in zone.offset_changes(start - MARGIN, end + MARGIN) {
+            let date = ctx.from_gregorian(change.local_time().date_naive());
+            if date >= first && date <= last {
+                changes.push((zone.name.as_str(), change));
+            }
+        }
     }
+    changes.sort_by_key(|(_, change)| change.at);
+    changes
 }
 
+/// Find the equinox or solstice of a month.
+fn season_in_month(ctx: &CalContext, year: i32, month: u32) -> Option<SeasonEvent> {
+    let season = Season::for_month(month)?;
+    let instant = to_display_zone(ctx, season_instant(year, season));
+    let date = ctx.from_gregorian(instant.date_naive());
+    Some((season, instant, date))
+}
+
+/// Format day cell with color highlighting.
+fn format_day(ctx: &CalContext, day: u32, month: u32, year: i32, weekday: Weekday) -> String {
+    let day_str = format!("{:>2}", day);
+
+    match day_color(ctx, year, month, day, weekday) {
+        Some(color) => format!("{}{}{}", color, day_str, COLOR_RESET),
+        None => day_str,
+    }
+}
+
+/// Separator after a day cell: `*` after a marked day, so marks survive without color.
+///
+/// The last cell of a row only gets a separator when marked; the mark then takes
+/// the first column of the gutter.
+fn day_separator(ctx: &CalContext, month: &MonthData, day: u32, is_last: bool) -> &'static str {
+    match is_marked_day(ctx, month.year, month.month, day) {
+        true => MARK,
+        false if is_last => "",
+        false => " ",
+    }
+}
+
+/// Marker of search matches, equinoxes, solstices, offset changes and polar days.
+const MARK: &str = "*";
+
 /// Format month as grid of lines (horizontal layout).
 pub fn format_month_grid(ctx: &CalContext, month: &MonthData) -> Vec<String> {
     let mut lines = Vec::with_capacity(8);
 
-    let header_width = if ctx.julian {
-        27
-    } else if ctx.week_numbers {
-        23
-    } else {
-        20
-    };
+    let header_width = ctx.month_width();
 
-    let month_header = format_month_header(
+    let month_header = format_month_header_in(
         month.year,
         month.month,
         header_width,
         ctx.show_year_in_header,
-        ctx.color,
+        ctx.color.then_some(ctx.palette.header.as_str()),
     );
     lines.push(month_header);
 
@@ -502,7 +782,9 @@ pub fn format_month_grid(ctx: &CalContext, month: &MonthData) -> Vec<String> {
                 .next();
 
             if let Some(wn) = week_wn {
-                line.push_str(&format!("{:>2} ", wn));
+                let number = format!("{:>2}", wn);
+                let color = week_number_color(ctx, month, day_idx);
+                line.push_str(&format!("{} ", paint(color, &number)));
             } else {
                 line.push_str("   ");
             }
@@ -517,23 +799,19 @@ pub fn format_month_grid(ctx: &CalContext, month: &MonthData) -> Vec<String> {
             if let Some(day) = month.days[day_idx] {
                 if ctx.julian {
                     let doy = ctx.day_of_year(month.year, month.month, day);
-                    let doy_str = format!("{:>3}", doy);
-                    if is_last {
-                        line.push_str(&doy_str);
-                    } else {
-                        line.push_str(&format!("{} ", doy_str));
-                    }
+                    // Day-of-year cells are only colored for the selected day
+                    let doy_str =
+                        if ctx.color && ctx.selected == Some((month.year, month.month, day)) {
+                            paint(Some(&ctx.palette.selected), &format!("{:>3}", doy))
+                        } else {
+                            format!("{:>3}", doy)
+                        };
+                    line.push_str(&doy_str);
                 } else {
                     let weekday = month.weekdays[day_idx].unwrap();
-                    line.push_str(&format_day(
-                        ctx,
-                        day,
-                        month.month,
-                        month.year,
-                        weekday,
-                        is_last,
-                    ));
+                    line.push_str(&format_day(ctx, day, month.month, month.year, weekday));
                 }
+                line.push_str(day_separator(ctx, month, day, is_last));
             } else if ctx.julian {
                 if is_last {
                     line.push_str("   ");
@@ -559,29 +837,104 @@ pub fn format_month_grid(ctx: &CalContext, month: &MonthData) -> Vec<String> {
 }
 
 /// Print single month in horizontal (default) or vertical layout.
-pub fn print_month(ctx: &CalContext, year: i32, month: u32) {
+pub fn print_month(
+    out: &mut impl Write,
+    ctx: &CalContext,
+    year: i32,
+    month: u32,
+) -> io::Result<()> {
     preload_holidays(ctx, year, month);
 
     let month_data = MonthData::new(ctx, year, month);
     if ctx.vertical {
-        print_month_vertical(ctx, &month_data, true);
+        print_month_vertical(out, ctx, &month_data, true)?;
     } else {
         let lines = format_month_grid(ctx, &month_data);
         for line in lines {
-            println!("{}", line);
+            writeln!(out, "{}", line)?;
         }
     }
+    print_footer(out, ctx, std::slice::from_ref(&month_data))?;
+    Ok(())
+}
+
+/// Format a single week as a strip: title, full weekday names and dates.
+pub fn format_week_strip(
+    ctx: &CalContext,
+    year: i32,
+    week: u32,
+    dates: &[NaiveDate; 7],
+) -> Vec<String> {
+    let locale = get_system_locale();
+    let mut names = Vec::with_capacity(7);
+    let mut cells = Vec::with_capacity(7);
+
+    for date in dates {
+        let name = get_weekday_name(date.weekday(), locale);
+        let (y, m, d) = ctx.from_gregorian(*date);
+        let month_abbr: String = get_month_name(m).chars().take(3).collect();
+        let text = format!("{} {}", d, month_abbr);
+        let width = name.width().max(text.width());
+
+        let padding = " ".repeat(width - text.width());
+        let cell = match day_color(ctx, y, m, d, date.weekday()) {
+            Some(color) => format!("{}{}{}{}", color, text, COLOR_RESET, padding),
+            None => format!("{}{}", text, padding),
+        };
+        names.push(format!("{:<width$}", name, width = width));
+        cells.push(cell);
+    }
+
+    let names = names.join("  ");
+    let title = format!("Week {}, {}", week, year);
+    vec![
+        center_text(&title, names.width()).trim_end().to_string(),
+        names.trim_end().to_string(),
+        cells.join("  ").trim_end().to_string(),
+    ]
+}
+
+/// Print a single week as a strip.
+pub fn print_week(
+    out: &mut impl Write,
+    ctx: &CalContext,
+    year: i32,
+    week: u32,
+) -> Result<(), CalError> {
+    let dates = ctx
+        .week_dates(year, week)
+        .ok_or_else(|| format!("Invalid week: {} (year {} has no such week)", week, year))?;
+    for date in [dates[0], dates[6]] {
+        let (y, m, _) = ctx.from_gregorian(date);
+        preload_holidays(ctx, y, m);
+    }
+    for line in format_week_strip(ctx, year, week, &dates) {
+        writeln!(out, "{}", line)?;
+    }
+    let notes = project_notes(ctx, dates[0], dates[6]);
+    if !notes.is_empty() {
+        writeln!(out)?;
+        for note in notes {
+            writeln!(out, "{}", note)?;
+        }
+    }
+    Ok(())
 }
 
 /// Print single month in vertical layout (days in columns).
-pub fn print_month_vertical(ctx: &CalContext, month: &MonthData, is_first: bool) {
+pub fn print_month_vertical(
+    out: &mut impl Write,
+    ctx: &CalContext,
+    month: &MonthData,
+    is_first: bool,
+) -> io::Result<()> {
     let month_name = get_month_name(month.month);
     let header = if ctx.show_year_in_header {
         format!("{} {}", month_name, month.year)
     } else {
         month_name.to_string()
     };
-    let month_width = 18;
+    let month_width = MONTH_WIDTH_VERTICAL;
 
     let padded_header = if is_first {
         format!(
@@ -599,11 +952,14 @@ pub fn print_month_vertical(ctx: &CalContext, month: &MonthData, is_first: bool)
         )
     };
 
-    if ctx.color {
-        println!("{}{}{}", COLOR_TEAL, padded_header, COLOR_RESET);
-    } else {
-        println!("{}", padded_header);
-    }
+    writeln!(
+        out,
+        "{}",
+        paint(
+            ctx.color.then_some(ctx.palette.header.as_str()),
+            &padded_header
+        )
+    )?;
 
     let locale = get_system_locale();
     let weekday_order = get_weekday_order(ctx.week_start);
@@ -614,68 +970,312 @@ pub fn print_month_vertical(ctx: &CalContext, month: &MonthData, is_first: bool)
 
     for (row, weekday) in weekday_order.iter().enumerate() {
         let day_short = &weekday_names[row];
-        if ctx.color {
-            print!("{}{}{}", COLOR_SAND_YELLOW, day_short, COLOR_RESET);
-        } else {
-            print!("{}", day_short);
-        }
+        write!(
+            out,
+            "{}",
+            paint(
+                ctx.color.then_some(ctx.palette.weekdays.as_str()),
+                day_short
+            )
+        )?;
 
         for week in 0..6 {
-            let day_idx = (*weekday as usize) + 7 * week;
+            let day_idx = row + 7 * week;
             if day_idx < month.days.len() {
                 if let Some(day) = month.days[day_idx] {
-                    print_day_vertical(ctx, day, month, *weekday);
+                    write!(out, "{}", format_day_vertical(ctx, day, month, *weekday))?;
                 } else {
-                    print!("   ");
+                    write!(out, "   ")?;
                 }
             }
         }
-        println!();
+        writeln!(out)?;
     }
+
+    if ctx.week_numbers {
+        writeln!(out, "  {}", vertical_week_numbers(ctx, month))?;
+    }
+    Ok(())
 }
 
-/// Print day cell in vertical layout with color highlighting.
-fn print_day_vertical(ctx: &CalContext, day: u32, month: &MonthData, weekday: Weekday) {
-    let is_today = ctx.color
-        && ctx.today.day() == day
-        && ctx.today.month() == month.month
-        && ctx.today.year() == month.year;
+/// Week numbers under the columns of a vertical month, one 3-wide cell per week.
+fn vertical_week_numbers(ctx: &CalContext, month: &MonthData) -> String {
+    (0..6)
+        .map(|week| {
+            let number = month
+                .week_numbers
+                .iter()
+                .skip(7 * week)
+                .take(7)
+                .flatten()
+                .next();
+            match number {
+                Some(wn) => format!(
+                    " {}",
+                    paint(
+                        week_number_color(ctx, month, 7 * week),
+                        &format!("{:>2}", wn)
+                    )
+                ),
+                None => "   ".to_string(),
+            }
+        })
+        .collect()
+}
 
-    let is_weekend = ctx.color && ctx.is_weekend(weekday);
-    let holiday_code = if ctx.color {
-        get_holiday_code(ctx, month.year, month.month, day)
-    } else {
-        0
+/// Format day cell in vertical layout with color highlighting.
+///
+/// Cells are padded on the left, so a marked day carries its `*` in front.
+fn format_day_vertical(ctx: &CalContext, day: u32, month: &MonthData, weekday: Weekday) -> String {
+    let digits = day.to_string();
+    let mut padding = " ".repeat(3 - digits.len());
+    if is_marked_day(ctx, month.year, month.month, day) {
+        padding.replace_range(padding.len() - 1.., MARK);
+    }
+    match day_color(ctx, month.year, month.month, day, weekday) {
+        Some(color) => format!("{}{}{}{}", padding, color, digits, COLOR_RESET),
+        None => format!("{}{}", padding, digits),
+    }
+}
+
+/// Format sunrise, sunset and day length for every day of the displayed months.
+fn sun_agenda(ctx: &CalContext, months: &[MonthData]) -> Vec<String> {
+    let Some(location) = &ctx.location else {
+        return Vec::new();
     };
-    let day_str = day.to_string();
-    let padding = 3 - day_str.len();
+    let locale = get_system_locale();
+    let mut lines = vec![format!("Sunrise, sunset and day length: {}", location.name)];
+    // "HH:MM  HH:MM", or a longer polar day or night name
+    let times_width = [Daylight::PolarDay, Daylight::PolarNight]
+        .iter()
+        .filter_map(|daylight| daylight.polar_name_in(locale))
+        .map(|name| name.width())
+        .fold(12, usize::max);
+
+    for month in months {
+        for (idx, day) in month.days.iter().enumerate() {
+            let (Some(day), Some(weekday)) = (*day, month.weekdays[idx]) else {
+                continue;
+            };
+            let daylight = sun_times(
+                ctx.julian_day_number(month.year, month.month, day),
+                location,
+            );
+            let times = match daylight {
+                Daylight::Normal { sunrise, sunset } => format!(
+                    "{}  {}",
+                    to_display_zone(ctx, sunrise).format("%H:%M"),
+                    to_display_zone(ctx, sunset).format("%H:%M")
+                ),
+                Daylight::PolarDay | Daylight::PolarNight => daylight
+                    .polar_name_in(locale)
+                    .unwrap_or_default()
+                    .to_string(),
+            };
+            let length = daylight.length_minutes();
+            lines.push(format!(
+                "{:04}-{:02}-{:02} {}  {:<width$}  {:02}:{:02}",
+                month.year,
+                month.month,
+                day,
+                get_weekday_short_name(weekday, locale),
+                times,
+                length / 60,
+                length % 60,
+                width = times_width
+            ));
+        }
+    }
 
-    let formatted = if is_today {
-        format!(
-            "{}{}{}{}",
-            " ".repeat(padding),
-            COLOR_REVERSE,
-            day,
-            COLOR_RESET
-        )
-    } else if holiday_code == 2 {
-        format!(
-            "{}{}{}{}",
-            " ".repeat(padding),
-            COLOR_TEAL,
+    lines
+}
+
+/// Format UTC offset changes of the displayed months.
+fn offset_change_notes(ctx: &CalContext, months: &[MonthData]) -> Vec<String> {
+    let mut notes = Vec::new();
+    for month in months {
+        let last_day = ctx.days_in_month(month.year, month.month);
+        let changes = offset_changes_between(
+            ctx,
+            (month.year, month.month, 1),
+            (month.year, month.month, last_day),
+        );
+        for (zone, change) in changes {
+            let (y, m, d) = ctx.from_gregorian(change.local_time().date_naive());
+            notes.push(format!(
+                "{:<width$} {:04}-{:02}-{:02} {}  {} ({}) -> {} ({})",
+                zone,
+                y,
+                m,
+                d,
+                change.local_time().format("%H:%M"),
+                change.before.abbreviation,
+                change.before.offset_string(),
+                change.after.abbreviation,
+                change.after.offset_string(),
+                width = zone_name_width(ctx)
+            ));
+        }
+    }
+    notes
+}
+
+/// Format a search match as "2026-02-13 Пт" or "2026-02 Февраль".
+pub fn format_match(ctx: &CalContext, found: &Match) -> String {
+    match *found {
+        Match::Day(year, month, day) => format!(
+            "{:04}-{:02}-{:02} {}",
+            year,
+            month,
             day,
-            COLOR_RESET
+            get_weekday_short_name(ctx.weekday(year, month, day), get_system_locale())
+        ),
+        Match::Month(year, month) => {
+            format!("{:04}-{:02} {}", year, month, get_month_name(month))
+        }
+    }
+}
+
+/// Print search matches, one per line.
+pub fn print_matches(out: &mut impl Write, ctx: &CalContext, matches: &[Match]) -> io::Result<()> {
+    for found in matches {
+        writeln!(out, "{}", format_match(ctx, found))?;
+    }
+    Ok(())
+}
+
+/// Print years with the same calendar layout, marking partial matches.
+pub fn print_same_layout(out: &mut impl Write, matches: &[(i32, LayoutMatch)]) -> io::Result<()> {
+    for &(year, kind) in matches {
+        match kind {
+            LayoutMatch::Full => writeln!(out, "{:04}", year)?,
+            LayoutMatch::JanFeb => writeln!(out, "{:04} (Jan-Feb)", year)?,
+            LayoutMatch::January => writeln!(out, "{:04} (Jan)", year)?,
+            LayoutMatch::MarDec => writeln!(out, "{:04} (Mar-Dec)", year)?,
+        }
+    }
+    Ok(())
+}
+
+/// Format search matches within the displayed months.
+fn match_notes(ctx: &CalContext, query: &Query, months: &[MonthData]) -> Vec<String> {
+    let matches: Vec<Match> = months
+        .iter()
+        .flat_map(|month| query.find_in_month(ctx, month.year, month.month))
+        .collect();
+    if matches.is_empty() {
+        return vec![format!("Matches for {}: none", query)];
+    }
+    let mut notes = vec![format!("Matches for {}:", query)];
+    notes.extend(matches.iter().map(|found| format_match(ctx, found)));
+    notes
+}
+
+/// Width of the zone name column in footer notes.
+fn zone_name_width(ctx: &CalContext) -> usize {
+    ctx.time_zones
+        .iter()
+        .map(|zone| zone.name.width())
+        .max()
+        .unwrap_or(0)
+        .max(18)
+}
+
+/// Format the current date and time in each zone when several zones are given.
+fn zone_clock_notes(ctx: &CalContext) -> Vec<String> {
+    if ctx.time_zones.len() < 2 {
+        return Vec::new();
+    }
+    let now = ctx.clock.now();
+    ctx.time_zones
+        .iter()
+        .map(|zone| {
+            let local = zone.to_local(now);
+            let abbreviation = zone.local_type_at(now.timestamp()).abbreviation;
+            format!(
+                "{:<width$} {} {}",
+                zone.name,
+                local.format("%Y-%m-%d %H:%M"),
+                abbreviation,
+                width = zone_name_width(ctx)
+            )
+        })
+        .collect()
+}
+
+/// Collect footer notes (zone clocks, equinoxes, solstices, offset changes, project events,
+/// sun agenda) for the displayed months.
+pub fn footer_notes(ctx: &CalContext, months: &[MonthData]) -> Vec<String> {
+    let mut notes = zone_clock_notes(ctx);
+
+    if let Some(query) = &ctx.find {
+        notes.extend(match_notes(ctx, query, months));
+    }
+
+    if ctx.seasons {
+        let locale = get_system_locale();
+        let width = Season::ALL
+            .iter()
+            .map(|season| season.name_in(locale).width())
+            .fold(18, usize::max);
+        for month in months {
+            if let Some((season, instant, (y, m, d))) =
+                season_in_month(ctx, month.year, month.month)
+            {
+                notes.push(format!(
+                    "{:<width$} {:04}-{:02}-{:02} {}",
+                    season.name_in(locale),
+                    y,
+                    m,
+                    d,
+                    instant.format("%H:%M %:z")
+                ));
+            }
+        }
+    }
+
+    if ctx.dst {
+        notes.extend(offset_change_notes(ctx, months));
+    }
+    if let (Some(first), Some(last)) = (months.first(), months.last())
+        && let Some(first) = ctx.to_gregorian(first.year, first.month, 1)
+        && let Some(last) = ctx.to_gregorian(
+            last.year,
+            last.month,
+            ctx.days_in_month(last.year, last.month),
         )
-    } else if is_weekend || holiday_code == 1 || holiday_code == 8 {
-        format!("{}{}{}{}", " ".repeat(padding), COLOR_RED, day, COLOR_RESET)
-    } else {
-        format!("{:>3}", day)
-    };
-    print!("{}", formatted);
+    {
+        notes.extend(project_notes(ctx, first, last));
+    }
+    notes.extend(sun_agenda(ctx, months));
+
+    notes
+}
+
+/// Print footer notes below the calendar, separated by an empty line.
+pub fn print_footer(
+    out: &mut impl Write,
+    ctx: &CalContext,
+    months: &[MonthData],
+) -> io::Result<()> {
+    let notes = footer_notes(ctx, months);
+    if notes.is_empty() {
+        return Ok(());
+    }
+    writeln!(out)?;
+    for note in notes {
+        writeln!(out, "{}", note)?;
+    }
+    Ok(())
 }
 
 /// Print three months side by side (prev, current, next).
-pub fn print_three_months(ctx: &CalContext, year: i32, month: u32) {
+pub fn print_three_months(
+    out: &mut impl Write,
+    ctx: &CalContext,
+    year: i32,
+    month: u32,
+) -> io::Result<()> {
     let prev_month = if month == 1 { 12 } else { month - 1 };
     let prev_year = if month == 1 { year - 1 } else { year };
     let next_month = if month == 12 { 1 } else { month + 1 };
@@ -692,38 +1292,40 @@ pub fn print_three_months(ctx: &CalContext, year: i32, month: u32) {
     ];
 
     if ctx.vertical {
-        print_three_months_vertical(ctx, &months);
+        print_three_months_vertical(out, ctx, &months)?;
     } else {
-        print_months_side_by_side(ctx, &months);
+        print_months_side_by_side(out, ctx, &months)?;
     }
+    print_footer(out, ctx, &months)?;
+    Ok(())
 }
 
 /// Print multiple months side by side in horizontal layout.
-pub fn print_months_side_by_side(ctx: &CalContext, months: &[MonthData]) {
+pub fn print_months_side_by_side(
+    out: &mut impl Write,
+    ctx: &CalContext,
+    months: &[MonthData],
+) -> io::Result<()> {
     let grids: Vec<Vec<String>> = months.iter().map(|m| format_month_grid(ctx, m)).collect();
     let max_height = grids.iter().map(|g| g.len()).max().unwrap_or(0);
 
-    let month_width: usize = if ctx.julian {
-        27
-    } else if ctx.week_numbers {
-        23
-    } else {
-        20
-    };
+    let month_width = ctx.month_width();
 
     for row in 0..max_height {
         let mut line = String::new();
         for (i, grid) in grids.iter().enumerate() {
             if row < grid.len() {
                 let text = &grid[row];
-                let text_width = text.width();
+                let text_width = visible_width(text);
                 line.push_str(text);
                 let padding = month_width.saturating_sub(text_width);
                 for _ in 0..padding {
                     line.push(' ');
                 }
+                // A mark after the last day takes the first column of the gutter
+                let overflow = text_width.saturating_sub(month_width);
                 if i < grids.len() - 1 {
-                    for _ in 0..ctx.gutter_width {
+                    for _ in overflow..ctx.gutter_width {
                         line.push(' ');
                     }
                 }
@@ -738,18 +1340,19 @@ pub fn print_months_side_by_side(ctx: &CalContext, months: &[MonthData]) {
                 }
             }
         }
-        println!("{}", line);
+        writeln!(out, "{}", line)?;
     }
+    Ok(())
 }
 
 /// Print all 12 months of a year.
-pub fn print_year(ctx: &CalContext, year: i32) {
+pub fn print_year(out: &mut impl Write, ctx: &CalContext, year: i32) -> io::Result<()> {
     if ctx.vertical {
-        println!("{}", center_text(&year.to_string(), 62));
+        writeln!(out, "{}", center_text(&year.to_string(), 62))?;
     } else {
-        println!("{}", center_text(&year.to_string(), 66));
+        writeln!(out, "{}", center_text(&year.to_string(), 66))?;
     }
-    println!();
+    writeln!(out)?;
 
     #[cfg(feature = "plugins")]
     if ctx.holidays {
@@ -777,18 +1380,62 @@ pub fn print_year(ctx: &CalContext, year: i32) {
 
     if ctx.vertical {
         for months in month_rows.iter() {
-            print_three_months_vertical(&month_ctx, months);
+            print_three_months_vertical(out, &month_ctx, months)?;
         }
     } else {
         for months in month_rows.iter() {
-            print_months_side_by_side(&month_ctx, months);
+            print_months_side_by_side(out, &month_ctx, months)?;
         }
     }
+    let months: Vec<MonthData> = month_rows.into_iter().flatten().collect();
+    print_footer(out, ctx, &months)?;
+    Ok(())
+}
+
+/// Print a titled quarter or half of a year.
+pub fn print_period(
+    out: &mut impl Write,
+    ctx: &CalContext,
+    year: i32,
+    period: Period,
+) -> io::Result<()> {
+    let mut month_ctx = ctx.clone();
+    month_ctx.show_year_in_header = false;
+
+    let months: Vec<MonthData> = (period.first_month()..)
+        .take(period.months() as usize)
+        .map(|month| {
+            preload_holidays(ctx, year, month);
+            MonthData::new(&month_ctx, year, month)
+        })
+        .collect();
+
+    let months_per_row = (ctx.months_per_row() as usize).min(months.len());
+    writeln!(
+        out,
+        "{}",
+        center_text(&period.title(year), ctx.row_width(months_per_row))
+    )?;
+    writeln!(out)?;
+
+    for chunk in months.chunks(months_per_row) {
+        if ctx.vertical {
+            print_three_months_vertical(out, &month_ctx, chunk)?;
+        } else {
+            print_months_side_by_side(out, &month_ctx, chunk)?;
+        }
+    }
+    print_footer(out, ctx, &months)?;
+    Ok(())
 }
 
 /// Print three months in vertical layout.
-pub fn print_three_months_vertical(ctx: &CalContext, months: &[MonthData]) {
-    let month_width = 18;
+pub fn print_three_months_vertical(
+    out: &mut impl Write,
+    ctx: &CalContext,
+    months: &[MonthData],
+) -> io::Result<()> {
+    let month_width = MONTH_WIDTH_VERTICAL;
 
     // Print headers
     for (i, month) in months.iter().enumerate() {
@@ -813,13 +1460,16 @@ pub fn print_three_months_vertical(ctx: &CalContext, months: &[MonthData]) {
                 width = month_width
             )
         };
-        if ctx.color {
-            print!("{}{}{}", COLOR_TEAL, padded_header, COLOR_RESET);
-        } else {
-            print!("{}", padded_header);
-        }
+        write!(
+            out,
+            "{}",
+            paint(
+                ctx.color.then_some(ctx.palette.header.as_str()),
+                &padded_header
+            )
+        )?;
     }
-    println!();
+    writeln!(out)?;
 
     let locale = get_system_locale();
     let weekday_order = get_weekday_order(ctx.week_start);
@@ -830,37 +1480,54 @@ pub fn print_three_months_vertical(ctx: &CalContext, months: &[MonthData]) {
 
     for (row, &weekday) in weekday_order.iter().enumerate() {
         let day_short = &weekday_names[row];
-        if ctx.color {
-            print!("{}{}{}", COLOR_SAND_YELLOW, day_short, COLOR_RESET);
-        } else {
-            print!("{}", day_short);
-        }
+        write!(
+            out,
+            "{}",
+            paint(
+                ctx.color.then_some(ctx.palette.weekdays.as_str()),
+                day_short
+            )
+        )?;
 
         for (month_idx, month) in months.iter().enumerate() {
             if month_idx > 0 {
                 for _ in 0..ctx.gutter_width {
-                    print!(" ");
+                    write!(out, " ")?;
                 }
             }
 
             for week in 0..6 {
-                let day_idx = (weekday as usize) + 7 * week;
+                let day_idx = row + 7 * week;
                 if day_idx < month.days.len() {
                     if let Some(day) = month.days[day_idx] {
-                        print_day_vertical(ctx, day, month, weekday);
+                        write!(out, "{}", format_day_vertical(ctx, day, month, weekday))?;
                     } else {
-                        print!("   ");
+                        write!(out, "   ")?;
                     }
                 }
             }
         }
-        println!();
+        writeln!(out)?;
+    }
+
+    if ctx.week_numbers {
+        let numbers: Vec<String> = months
+            .iter()
+            .map(|month| vertical_week_numbers(ctx, month))
+            .collect();
+        writeln!(out, "  {}", numbers.join(&" ".repeat(ctx.gutter_width)))?;
     }
-    println!();
+    writeln!(out)?;
+    Ok(())
 }
 
 /// Print 12 months starting from a given month (--twelve mode).
-pub fn print_twelve_months(ctx: &CalContext, start_year: i32, start_month: u32) {
+pub fn print_twelve_months(
+    out: &mut impl Write,
+    ctx: &CalContext,
+    start_year: i32,
+    start_month: u32,
+) -> io::Result<()> {
     // Preload holiday data for all 12 months
     #[cfg(feature = "plugins")]
     if ctx.holidays {
@@ -893,23 +1560,26 @@ pub fn print_twelve_months(ctx: &CalContext, start_year: i32, start_month: u32)
 
     if ctx.vertical {
         for month_data in &months {
-            print_month_vertical(&month_ctx, month_data, true);
-            println!();
+            print_month_vertical(out, &month_ctx, month_data, true)?;
+            writeln!(out)?;
         }
     } else {
         for chunk in months.chunks(3) {
-            print_months_side_by_side(&month_ctx, chunk);
+            print_months_side_by_side(out, &month_ctx, chunk)?;
         }
     }
+    print_footer(out, ctx, &months)?;
+    Ok(())
 }
 
 /// Print a specified number of months (-n mode).
 pub fn print_months_count(
+    out: &mut impl Write,
     ctx: &CalContext,
     start_year: i32,
     start_month: u32,
     count: u32,
-) -> Result<(), String> {
+) -> io::Result<()> {
     let months_per_row = ctx.months_per_row();
 
     // Calculate start month for span mode (center around current month)
@@ -960,14 +1630,15 @@ pub fn print_months_count(
 
     if ctx.vertical {
         for month_data in &months {
-            print_month_vertical(ctx, month_data, true);
-            println!();
+            print_month_vertical(out, ctx, month_data, true)?;
+            writeln!(out)?;
         }
     } else {
         for chunk in months.chunks(months_per_row as usize) {
-            print_months_side_by_side(ctx, chunk);
+            print_months_side_by_side(out, ctx, chunk)?;
         }
     }
+    print_footer(out, ctx, &months)?;
 
     Ok(())
 }
@@ -0,0 +1,183 @@
+//! Facts about a year, month or date (--info).
+
+use chrono::{Datelike, NaiveDate};
+use std::io::{self, Write};
+
+use crate::args::{Args, parse_display_date};
+use crate::error::{CalError, ErrorKind};
+#[cfg(feature = "plugins")]
+use crate::formatter::{get_holiday_code, plugin_available};
+use crate::formatter::{get_month_name, get_system_locale, get_weekday_name};
+use crate::types::CalContext;
+
+/// What the report describes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InfoScope {
+    Year(i32),
+    Month(i32, u32),
+    Day(i32, u32, u32),
+}
+
+impl InfoScope {
+    /// Pick the scope from positional arguments: a year, a month, or a date (today by default).
+    pub fn from_args(ctx: &CalContext, args: &Args) -> Result<Self, CalError> {
+        let date = parse_display_date(args, ctx)?;
+        if date.months.is_some() || date.period.is_some() {
+            return Err(CalError::new(
+                ErrorKind::Argument,
+                "--info takes a year, a month or a date, not a range or period",
+            ));
+        }
+        let scope = match date.day {
+            Some(day) => InfoScope::Day(date.year, date.month, day),
+            None if args.day_arg.is_none() => {
+                let (year, month, day) = ctx.from_gregorian(ctx.today);
+                InfoScope::Day(year, month, day)
+            }
+            None if date.whole_year => InfoScope::Year(date.year),
+            None => InfoScope::Month(date.year, date.month),
+        };
+
+        if let InfoScope::Day(year, month, day) = scope
+            && ctx.is_reform_gap(year, month, day)
+        {
+            return Err(CalError::new(
+                ErrorKind::Day,
+                format!("Invalid day: {} (skipped by the calendar reform)", day),
+            ));
+        }
+        Ok(scope)
+    }
+}
+
+/// Build the report as (label, value) rows.
+pub fn info_report(ctx: &CalContext, scope: InfoScope) -> Vec<(&'static str, String)> {
+    let locale = get_system_locale();
+    let weekday_name = |y, m, d| get_weekday_name(ctx.weekday(y, m, d), locale);
+    let yes_no = |b: bool| if b { "yes" } else { "no" }.to_string();
+    let quarter = |m: u32| format!("Q{}", (m - 1) / 3 + 1);
+
+    match scope {
+        InfoScope::Year(year) => vec![
+            ("Year", year.to_string()),
+            ("Leap year", yes_no(ctx.is_leap_year(year))),
+            ("Days", ctx.days_in_year(year).to_string()),
+            ("ISO weeks", iso_weeks_in_year(year).to_string()),
+            ("First weekday", weekday_name(year, 1, 1)),
+            ("Last weekday", weekday_name(year, 12, 31)),
+            ("Easter", easter(ctx, year)),
+        ],
+        InfoScope::Month(year, month) => {
+            let last_day = ctx.days_in_month(year, month);
+            let days = (1..=last_day)
+                .filter(|&day| !ctx.is_reform_gap(year, month, day))
+                .count();
+            vec![
+                ("Month", format!("{} {}", get_month_name(month), year)),
+                ("Days", days.to_string()),
+                ("First weekday", weekday_name(year, month, 1)),
+                ("Last weekday", weekday_name(year, month, last_day)),
+                ("Quarter", quarter(month)),
+                ("Leap year", yes_no(ctx.is_leap_year(year))),
+            ]
+        }
+        InfoScope::Day(year, month, day) => {
+            let day_of_year = ctx.day_of_year(year, month, day);
+            let mut rows = vec![
+                (
+                    "Date",
+                    format!(
+                        "{:04}-{:02}-{:02} {}",
+                        year,
+                        month,
+                        day,
+                        weekday_name(year, month, day)
+                    ),
+                ),
+                ("Day of year", day_of_year.to_string()),
+                (
+                    "Days remaining",
+                    (ctx.days_in_year(year) - day_of_year).to_string(),
+                ),
+            ];
+            if let Some(date) = ctx.to_gregorian(year, month, day) {
+                rows.push(("ISO week date", iso_week_date(date)));
+            }
+            rows.extend([
+                (
+                    "Julian Day Number",
+                    ctx.julian_day_number(year, month, day).to_string(),
+                ),
+                ("Quarter", quarter(month)),
+                ("Leap year", yes_no(ctx.is_leap_year(year))),
+                ("Easter", easter(ctx, year)),
+            ]);
+            #[cfg(feature = "plugins")]
+            if ctx.holidays {
+                rows.push(("Holiday code", holiday_code(ctx, year, month, day)));
+            }
+            rows
+        }
+    }
+}
+
+/// Print the report with aligned values.
+pub fn print_info(out: &mut impl Write, ctx: &CalContext, scope: InfoScope) -> io::Result<()> {
+    let rows = info_report(ctx, scope);
+    let width = rows.iter().map(|(label, _)| label.len()).max().unwrap_or(0) + 1;
+    for (label, value) in rows {
+        writeln!(
+            out,
+            "{:<width$} {}",
+            format!("{}:", label),
+            value,
+            width = width
+        )?;
+    }
+    Ok(())
+}
+
+/// Number of ISO 8601 weeks (52 or 53) in a Gregorian year.
+fn iso_weeks_in_year(year: i32) -> u32 {
+    NaiveDate::from_ymd_opt(year, 12, 28)
+        .map(|date| date.iso_week().week())
+        .unwrap_or(52)
+}
+
+/// Format ISO 8601 week date ("2026-W08-3").
+fn iso_week_date(date: NaiveDate) -> String {
+    let week = date.iso_week();
+    format!(
+        "{:04}-W{:02}-{}",
+        week.year(),
+        week.week(),
+        date.weekday().number_from_monday()
+    )
+}
+
+/// Describe the holiday plugin code for a date.
+#[cfg(feature = "plugins")]
+fn holiday_code(ctx: &CalContext, year: i32, month: u32, day: u32) -> String {
+    if !plugin_available(ctx) {
+        return "unavailable (plugin not loaded)".to_string();
+    }
+    let code = get_holiday_code(ctx, year, month, day);
+    let meaning = match code {
+        0 => "working day",
+        1 => "weekend",
+        2 => "shortened working day",
+        8 => "public holiday",
+        _ => "unknown",
+    };
+    format!("{} ({})", code, meaning)
+}
+
+/// Format Easter Sunday, noting the Julian computus before the reform.
+fn easter(ctx: &CalContext, year: i32) -> String {
+    let (month, day) = ctx.easter(year);
+    if year < ctx.reform_year {
+        format!("{:04}-{:02}-{:02} (Julian)", year, month, day)
+    } else {
+        format!("{:04}-{:02}-{:02}", year, month, day)
+    }
+}
@@ -4,12 +4,32 @@
 //! - Gregorian and Julian calendar support
 //! - Customizable week start (Monday/Sunday)
 //! - Week numbers and Julian day display
+//! - Equinox and solstice markers, sunrise and sunset
+//! - Daylight saving time transitions from the system zoneinfo
+//! - Search queries ("fri 13", "last mon of may")
+//! - Year, month and date facts (leap year, ISO week date, Easter, ...)
+//! - Diagnostics with suggestions and distinct exit codes
+//! - Config file with named profiles
+//! - Project calendars (.calendar.toml) with releases, code freezes and sprints
+//! - util-linux compatible layout (--compat=util-linux)
+//! - BSD ncal personality (run as ncal or with --ncal)
 //! - Plugin system for holiday highlighting
 
 pub mod args;
+pub mod astro;
 pub mod calendar;
+pub mod clock;
+pub mod colors;
+pub mod compat;
+pub mod config;
+pub mod error;
 pub mod formatter;
+pub mod info;
+pub mod ncal;
+pub mod project;
+pub mod query;
 pub mod types;
+pub mod zoneinfo;
 
 #[cfg(feature = "plugins")]
 pub mod plugin_api;
@@ -9,37 +9,191 @@
 //! cal -y       // Whole year
 //! ```
 
-use cal::args::{Args, get_display_date};
+use chrono::Datelike;
+use std::io::{self, BufWriter, Write};
+
+use cal::args::{
+    Args, deprecation_warning, lone_number_note, parse_year, parse_year_range, week_to_show,
+};
+use cal::compat;
+use cal::error::{CalError, ErrorKind};
 use cal::formatter::{
-    print_month, print_months_count, print_three_months, print_twelve_months, print_year,
+    print_matches, print_month, print_months_count, print_period, print_same_layout,
+    print_three_months, print_twelve_months, print_week, print_year,
 };
-use cal::types::CalContext;
+use cal::info::{InfoScope, print_info};
+use cal::ncal;
+use cal::types::{CalContext, Period};
 
 fn main() {
     let args = Args::parse();
+    if let Some(warning) = deprecation_warning(&args) {
+        eprintln!("cal: warning: {}", warning);
+    }
 
-    if let Err(e) = run(&args) {
+    let result = CalContext::new(&args)
+        .map_err(CalError::from)
+        .and_then(|mut ctx| {
+            let mut out = BufWriter::new(io::stdout().lock());
+            let result = run(&mut out, &args, &mut ctx).and_then(|()| Ok(out.flush()?));
+            // A broken project file only costs the overlay
+            if let Some(e) = ctx.project.error() {
+                eprintln!("cal: warning: {}", e);
+            }
+            result
+        });
+    if let Err(e) = result {
+        // The reader has gone away (cal -n 5000 | head): nothing left to say
+        if e.kind == ErrorKind::Output(io::ErrorKind::BrokenPipe) {
+            std::process::exit(0);
+        }
         eprintln!("cal: {}", e);
-        std::process::exit(1);
+        if let Some(hint) = &e.hint {
+            eprintln!("cal: hint: {}", hint);
+        }
+        std::process::exit(e.exit_code());
     }
 }
 
-fn run(args: &Args) -> Result<(), String> {
-    let ctx = CalContext::new(args)?;
-    let (year, month, _day) = get_display_date(args)?;
+fn run(out: &mut impl Write, args: &Args, ctx: &mut CalContext) -> Result<(), CalError> {
+    // Search over a year range lists matches instead of drawing a calendar
+    if let Some(query) = &ctx.find
+        && let Some(arg) = &args.day_arg
+        && let Some((first, last)) = parse_year_range(arg)?
+    {
+        print_matches(out, ctx, &query.find(ctx, first, last))?;
+        return Ok(());
+    }
+
+    // Years with the same layout, in the given range or 100 years either side
+    if let Some(year) = args.same_as {
+        let range = match &args.day_arg {
+            Some(arg) => parse_year_range(arg)?.ok_or_else(|| {
+                CalError::new(
+                    ErrorKind::Argument,
+                    format!("Invalid year range: {} (expected e.g. 1900..2100)", arg),
+                )
+            })?,
+            None => ((year - 100).max(1), (year + 100).min(9999)),
+        };
+        print_same_layout(
+            out,
+            &ctx.same_layout_years(year, range.0, range.1, args.partial),
+        )?;
+        return Ok(());
+    }
+
+    if args.reforms {
+        for line in ncal::reform_table(ctx) {
+            writeln!(out, "{}", line)?;
+        }
+        return Ok(());
+    }
+
+    if let Some(church) = args.easter {
+        let year = match &args.day_arg {
+            Some(year) => parse_year(year)?,
+            None => ctx.today.year(),
+        };
+        writeln!(out, "{}", ncal::format_easter(ctx, year, church))?;
+        return Ok(());
+    }
+
+    if args.info {
+        print_info(out, ctx, InfoScope::from_args(ctx, args)?)?;
+        return Ok(());
+    }
+
+    if args.week.is_some() {
+        if args.year || args.twelve_months || args.three_months || args.months_count.is_some() {
+            return Err("Options -y, -Y, -3, and -n cannot be used with --week".into());
+        }
+        let (year, week) = week_to_show(args, ctx)?;
+        return print_week(out, ctx, year, week);
+    }
 
-    // Display mode priority: year > twelve_months > three_months > months_count > single
-    if args.year {
-        print_year(&ctx, year);
+    let date = ctx.date.clone()?;
+    let (year, month) = (date.year, date.month);
+    if let Some(note) = lone_number_note(args) {
+        eprintln!("cal: note: {}", note);
+    }
+
+    // A range, quarter or half shows exactly the given months
+    let period = match date.period {
+        None if args.quarter && date.months.is_some() => {
+            return Err("Option --quarter cannot be used with a range".into());
+        }
+        None if args.quarter => Some(Period::quarter_of(month)),
+        period => period,
+    };
+    if date.months.is_some() || period.is_some() {
+        if args.year || args.twelve_months || args.three_months || args.months_count.is_some() {
+            return Err("Options -y, -Y, -3, and -n cannot be used with a range or quarter".into());
+        }
+        match period {
+            _ if ctx.compat => {
+                let count = period.map_or(date.months.unwrap_or(1), Period::months);
+                compat::print_lines(out, &compat::format_months(ctx, year, month, count, false))?;
+            }
+            Some(period) => print_period(out, ctx, year, period)?,
+            None => {
+                ctx.span = false;
+                print_months_count(out, ctx, year, month, date.months.unwrap_or(1))?;
+            }
+        }
+        return Ok(());
+    }
+
+    // Display mode priority: year > twelve_months > three_months > months_count > single;
+    // util-linux shows the whole year for a lone year argument ("cal 2026")
+    let whole_year = args.year
+        || (ctx.compat
+            && date.whole_year
+            && !args.twelve_months
+            && !args.three_months
+            && args.months_count.is_none());
+    if ctx.compat {
+        print_compat(out, ctx, args, year, month, whole_year)?;
+    } else if whole_year {
+        print_year(out, ctx, year)?;
     } else if args.twelve_months {
-        print_twelve_months(&ctx, year, month);
+        print_twelve_months(out, ctx, year, month)?;
     } else if args.three_months {
-        print_three_months(&ctx, year, month);
+        print_three_months(out, ctx, year, month)?;
     } else if let Some(count) = args.months_count {
-        print_months_count(&ctx, year, month, count)?;
+        print_months_count(out, ctx, year, month, count)?;
     } else {
-        print_month(&ctx, year, month);
+        print_month(out, ctx, year, month)?;
     }
 
     Ok(())
 }
+
+/// Print in the util-linux layout.
+fn print_compat(
+    out: &mut impl Write,
+    ctx: &CalContext,
+    args: &Args,
+    year: i32,
+    month: u32,
+    whole_year: bool,
+) -> io::Result<()> {
+    let lines = if whole_year {
+        compat::format_year(ctx, year)
+    } else if args.twelve_months {
+        compat::format_months(ctx, year, month, 12, true)
+    } else {
+        let count = match (args.three_months, args.months_count) {
+            (true, _) => 3,
+            (false, Some(count)) => count,
+            (false, None) => 1,
+        };
+        let (year, month) = if args.three_months || ctx.span {
+            compat::span_start(year, month, count)
+        } else {
+            (year, month)
+        };
+        compat::format_months(ctx, year, month, count, false)
+    };
+    compat::print_lines(out, &lines)
+}
@@ -0,0 +1,240 @@
+//! BSD ncal personality: option translation, Easter dates and the country reform list.
+//!
+//! When run as `ncal` (or with `--ncal`) the ncal short options are rewritten to the
+//! native ones before parsing, and the vertical layout becomes the default.
+
+use std::ffi::OsString;
+use std::path::Path;
+
+use chrono::{Locale, NaiveDate};
+use clap::Command;
+
+use crate::formatter::get_system_locale;
+use crate::types::{CalContext, Easter, REFORM_YEAR_GB};
+
+/// Country Gregorian reform dates as in FreeBSD ncal: code, name and last Julian day.
+pub const SWITCHES: &[(&str, &str, (i32, u32, u32))] = &[
+    ("AL", "Albania", (1912, 11, 30)),
+    ("AT", "Austria", (1583, 10, 5)),
+    ("AU", "Australia", (1752, 9, 2)),
+    ("BE", "Belgium", (1582, 12, 14)),
+    ("BG", "Bulgaria", (1916, 3, 31)),
+    ("CA", "Canada", (1752, 9, 2)),
+    ("CH", "Switzerland", (1655, 2, 28)),
+    ("CN", "China", (1911, 12, 18)),
+    ("CZ", "Czech Republic", (1584, 1, 6)),
+    ("DE", "Germany", (1700, 2, 18)),
+    ("DK", "Denmark", (1700, 2, 18)),
+    ("ES", "Spain", (1582, 10, 4)),
+    ("FI", "Finland", (1753, 2, 17)),
+    ("FR", "France", (1582, 12, 9)),
+    ("GB", "United Kingdom", (1752, 9, 2)),
+    ("GR", "Greece", (1924, 3, 9)),
+    ("HU", "Hungary", (1587, 10, 21)),
+    ("IS", "Iceland", (1700, 11, 16)),
+    ("IT", "Italy", (1582, 10, 4)),
+    ("JP", "Japan", (1918, 12, 18)),
+    ("LI", "Lithuania", (1918, 2, 1)),
+    ("LN", "Latin", (9999, 5, 31)),
+    ("LU", "Luxembourg", (1582, 12, 14)),
+    ("LV", "Latvia", (1918, 2, 1)),
+    ("NL", "Netherlands", (1582, 12, 14)),
+    ("NO", "Norway", (1700, 2, 18)),
+    ("PL", "Poland", (1582, 10, 4)),
+    ("PT", "Portugal", (1582, 10, 4)),
+    ("RO", "Romania", (1919, 3, 31)),
+    ("RU", "Russia", (1918, 1, 31)),
+    ("SI", "Slovenia", (1919, 3, 4)),
+    ("SE", "Sweden", (1753, 2, 17)),
+    ("TR", "Turkey", (1926, 12, 18)),
+    ("US", "United States", (1752, 9, 2)),
+    ("YU", "Yugoslavia", (1919, 3, 4)),
+];
+
+/// Whether the command line asks for the ncal personality (program name or --ncal).
+pub fn is_ncal(argv: &[OsString]) -> bool {
+    let program = argv
+        .first()
+        .and_then(|arg0| Path::new(arg0).file_name())
+        .is_some_and(|name| name == "ncal");
+    program
+        || argv
+            .iter()
+            .skip(1)
+            .take_while(|arg| *arg != "--")
+            .any(|arg| arg == "--ncal")
+}
+
+/// Rewrite ncal options to native ones; other command lines are returned unchanged.
+///
+/// `-b`/`-C` keep the horizontal layout, `-e`/`-o` print Easter, `-p` the reform list,
+/// `-J` uses the Julian calendar, `-M`/`-S` set the week start, `-m` takes a month,
+/// `-s` a country code and `-h` turns off highlighting.
+pub fn translate(argv: Vec<OsString>, cmd: &Command) -> Result<Vec<OsString>, String> {
+    if !is_ncal(&argv) {
+        return Ok(argv);
+    }
+
+    let mut argv = argv.into_iter();
+    let mut out: Vec<OsString> = argv.next().into_iter().collect();
+    out.push("--ncal".into());
+    let mut first_positional = None;
+    let mut month = None;
+    let mut vertical = true;
+
+    while let Some(arg) = argv.next() {
+        let text = arg.to_string_lossy().into_owned();
+        if text == "--" {
+            first_positional.get_or_insert(out.len());
+            out.push(arg);
+            out.extend(argv.by_ref());
+            break;
+        }
+        if text == "--ncal" {
+            continue;
+        }
+        if let Some(long) = text.strip_prefix("--") {
+            out.push(arg);
+            // A value given as a separate word stays with its option
+            if !long.contains('=') && long_takes_value(cmd, long) {
+                out.extend(argv.next());
+            }
+            continue;
+        }
+        if !text.starts_with('-') || text == "-" {
+            first_positional.get_or_insert(out.len());
+            out.push(arg);
+            continue;
+        }
+
+        let cluster: Vec<char> = text.chars().skip(1).collect();
+        for (i, &flag) in cluster.iter().enumerate() {
+            let rest: String = cluster[i + 1..].iter().collect();
+            let mut value = || {
+                if rest.is_empty() {
+                    argv.next()
+                        .map(|value| value.to_string_lossy().into_owned())
+                        .ok_or_else(|| format!("Option -{} requires an argument", flag))
+                } else {
+                    Ok(rest.clone())
+                }
+            };
+            match flag {
+                'b' | 'C' => vertical = false,
+                'e' => out.push("--easter".into()),
+                'o' => out.push("--easter=orthodox".into()),
+                'p' => out.push("--reforms".into()),
+                'J' => out.push("--reform=julian".into()),
+                'M' => out.push("--monday".into()),
+                'S' => out.push("--sunday".into()),
+                'h' => out.push("--color=never".into()),
+                'm' => {
+                    month = Some(value()?);
+                    break;
+                }
+                's' => {
+                    out.push(country_reform(&value()?)?.into());
+                    break;
+                }
+                // An optional value attached with '=' ends the cluster
+                _ if rest.starts_with('=') => {
+                    out.push(format!("-{}{}", flag, rest).into());
+                    break;
+                }
+                _ if short_takes_value(cmd, flag) => {
+                    out.push(format!("-{}", flag).into());
+                    out.push(value()?.into());
+                    break;
+                }
+                _ => out.push(format!("-{}", flag).into()),
+            }
+        }
+    }
+
+    if let Some(month) = month {
+        let at = first_positional.unwrap_or(out.len());
+        out.insert(at, month.into());
+    }
+    if vertical {
+        out.insert(2, "--vertical".into());
+    }
+    Ok(out)
+}
+
+/// Native --reform option for an ncal country code.
+fn country_reform(code: &str) -> Result<String, String> {
+    let code = code.to_uppercase();
+    let Some((_, name, (year, month, day))) = SWITCHES.iter().find(|(cc, _, _)| *cc == code) else {
+        return Err(format!("Unknown country code: {} (see ncal -p)", code));
+    };
+    match code.as_str() {
+        "LN" => Ok("--reform=julian".to_string()),
+        _ if (*year, *month, *day) == (REFORM_YEAR_GB, 9, 2) => Ok("--reform=1752".to_string()),
+        _ => Err(format!(
+            "Reform date of {} ({:04}-{:02}-{:02}) is not supported; use --reform (1752, gregorian or julian)",
+            name, year, month, day
+        )),
+    }
+}
+
+fn long_takes_value(cmd: &Command, long: &str) -> bool {
+    cmd.get_arguments()
+        .find(|arg| arg.get_long() == Some(long))
+        .is_some_and(|arg| arg.get_action().takes_values() && !arg.is_require_equals_set())
+}
+
+fn short_takes_value(cmd: &Command, short: char) -> bool {
+    cmd.get_arguments()
+        .find(|arg| arg.get_short() == Some(short))
+        .is_some_and(|arg| arg.get_action().takes_values() && !arg.is_require_equals_set())
+}
+
+/// Format the reform list in two columns; `*` marks the reform in use.
+pub fn reform_table(ctx: &CalContext) -> Vec<String> {
+    let current = match ctx.reform_year {
+        REFORM_YEAR_GB => Some("GB"),
+        i32::MAX => Some("LN"),
+        _ => None,
+    };
+    let entry = |(code, name, (year, month, day)): &(&str, &str, (i32, u32, u32))| {
+        let mark = if current == Some(*code) { '*' } else { ' ' };
+        format!(
+            "{}{} {:<15}{:4}-{:02}-{:02}",
+            mark, code, name, year, month, day
+        )
+    };
+
+    let rows = SWITCHES.len() / 2;
+    let offset = SWITCHES.len().div_ceil(2);
+    let mut lines: Vec<String> = (0..rows)
+        .map(|row| {
+            format!(
+                "{}     {}",
+                entry(&SWITCHES[row]),
+                entry(&SWITCHES[row + offset])
+            )
+        })
+        .collect();
+    if rows != offset {
+        lines.push(entry(&SWITCHES[rows]));
+    }
+    lines
+}
+
+/// Format the date of Easter Sunday as ncal -e/-o does ("April  5 2026").
+pub fn format_easter(ctx: &CalContext, year: i32, church: Easter) -> String {
+    let (month, day) = match church {
+        Easter::Western => ctx.easter(year),
+        Easter::Orthodox => ctx.orthodox_easter(year),
+    };
+    let locale = get_system_locale();
+    let format = if locale == Locale::en_US {
+        "%B %e %Y"
+    } else {
+        "%e %B %Y"
+    };
+    // Only names the date: a Julian date before the reform is labelled as is
+    NaiveDate::from_ymd_opt(year, month, day)
+        .map(|date| date.format_localized(format, locale).to_string())
+        .unwrap_or_default()
+}
@@ -0,0 +1,289 @@
+//! Per-project calendar (.calendar.toml) discovered from the working directory.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use chrono::NaiveDate;
+use toml::{Table, Value};
+
+/// File name searched in the working directory and its parents.
+pub const PROJECT_FILE: &str = ".calendar.toml";
+
+/// Kind of project event.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EventKind {
+    /// Project day off.
+    Holiday,
+    /// Release date.
+    Release,
+    /// Code-freeze window.
+    Freeze,
+}
+
+impl EventKind {
+    /// Label used when the event has no name.
+    fn default_name(self) -> &'static str {
+        match self {
+            EventKind::Holiday => "Holiday",
+            EventKind::Release => "Release",
+            EventKind::Freeze => "Code freeze",
+        }
+    }
+}
+
+/// Dated project event; single-day events have start == end.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectEvent {
+    pub kind: EventKind,
+    pub name: String,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+/// Fixed-length sprints numbered from 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sprints {
+    pub start: NaiveDate,
+    pub length: u32,
+    pub name: String,
+}
+
+/// Project calendar: events and sprint cadence, dates in the Gregorian calendar.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectCalendar {
+    pub path: PathBuf,
+    pub events: Vec<ProjectEvent>,
+    pub sprints: Option<Sprints>,
+}
+
+/// Project calendar that is only looked up and read when a view first needs it,
+/// so that a broken file does not affect output that makes no use of it.
+#[derive(Debug, Clone, Default)]
+pub struct LazyProject {
+    /// Directory to look for the project file from (none: no project calendar).
+    dir: Option<PathBuf>,
+    loaded: OnceLock<Result<Option<ProjectCalendar>, String>>,
+}
+
+impl LazyProject {
+    /// Look the project file up from the working directory on first use.
+    pub fn discover() -> Self {
+        Self::discover_in(std::env::current_dir().ok())
+    }
+
+    /// Look the project file up from a directory on first use.
+    pub fn discover_in(dir: Option<PathBuf>) -> Self {
+        LazyProject {
+            dir,
+            loaded: OnceLock::new(),
+        }
+    }
+
+    /// The project calendar, if any; a file that cannot be read counts as none.
+    pub fn get(&self) -> Option<&ProjectCalendar> {
+        self.load().as_ref().ok()?.as_ref()
+    }
+
+    /// Why the project file could not be used, if it was looked up and is broken.
+    pub fn error(&self) -> Option<&str> {
+        self.loaded.get()?.as_ref().err().map(String::as_str)
+    }
+
+    fn load(&self) -> &Result<Option<ProjectCalendar>, String> {
+        self.loaded.get_or_init(|| match &self.dir {
+            Some(dir) => ProjectCalendar::find(dir),
+            None => Ok(None),
+        })
+    }
+}
+
+impl From<ProjectCalendar> for LazyProject {
+    fn from(project: ProjectCalendar) -> Self {
+        LazyProject {
+            dir: None,
+            loaded: OnceLock::from(Ok(Some(project))),
+        }
+    }
+}
+
+/// Find .calendar.toml in the directory or its nearest ancestor, like .editorconfig.
+pub fn discover(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .map(|dir| dir.join(PROJECT_FILE))
+        .find(|path| path.is_file())
+}
+
+impl ProjectCalendar {
+    /// Load the project file found from the directory, if any.
+    pub fn find(dir: &Path) -> Result<Option<Self>, String> {
+        let Some(path) = discover(dir) else {
+            return Ok(None);
+        };
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        Self::parse(&text, path.clone())
+            .map(Some)
+            .map_err(|e| format!("{} (in {})", e, path.display()))
+    }
+
+    /// Parse project file text.
+    pub fn parse(text: &str, path: PathBuf) -> Result<Self, String> {
+        let table: Table = text
+            .parse()
+            .map_err(|e: toml::de::Error| format!("Invalid project calendar: {}", e.message()))?;
+
+        let mut events = Vec::new();
+        for (key, kind) in [
+            ("holidays", EventKind::Holiday),
+            ("releases", EventKind::Release),
+            ("freezes", EventKind::Freeze),
+        ] {
+            let entries = match table.get(key) {
+                Some(Value::Array(entries)) => entries.as_slice(),
+                Some(_) => {
+                    return Err(format!(
+                        "Invalid project calendar: '{}' must be an array",
+                        key
+                    ));
+                }
+                None => &[],
+            };
+            for entry in entries {
+                events.push(parse_event(entry, kind)?);
+            }
+        }
+        events.sort_by_key(|event| event.start);
+
+        let sprints = match table.get("sprints") {
+            Some(Value::Table(sprints)) => {
+                let start = date_field(sprints, "start")?
+                    .ok_or("Invalid project calendar: sprints need a start date")?;
+                let length = match (sprints.get("length"), sprints.get("weeks")) {
+                    (Some(Value::Integer(days)), _) => *days,
+                    (None, Some(Value::Integer(weeks))) => weeks * 7,
+                    (None, None) => 14,
+                    _ => {
+                        return Err(
+                            "Invalid project calendar: sprint length must be a number".to_string()
+                        );
+                    }
+                };
+                if !(1..=366).contains(&length) {
+                    return Err(format!(
+                        "Invalid project calendar: sprint length {} days",
+                        length
+                    ));
+                }
+                Some(Sprints {
+                    start,
+                    length: length as u32,
+                    name: string_field(sprints, "name").unwrap_or_else(|| "Sprint".to_string()),
+                })
+            }
+            Some(_) => {
+                return Err("Invalid project calendar: 'sprints' must be a table".to_string());
+            }
+            None => None,
+        };
+
+        Ok(ProjectCalendar {
+            path,
+            events,
+            sprints,
+        })
+    }
+
+    /// Most important event on a date: release > holiday > freeze.
+    pub fn event_on(&self, date: NaiveDate) -> Option<EventKind> {
+        let on = |kind| {
+            self.events
+                .iter()
+                .any(|event| event.kind == kind && event.start <= date && date <= event.end)
+        };
+        [EventKind::Release, EventKind::Holiday, EventKind::Freeze]
+            .into_iter()
+            .find(|&kind| on(kind))
+    }
+
+    /// Events overlapping an inclusive date range.
+    pub fn events_between(&self, first: NaiveDate, last: NaiveDate) -> Vec<&ProjectEvent> {
+        self.events
+            .iter()
+            .filter(|event| event.start <= last && first <= event.end)
+            .collect()
+    }
+
+    /// Sprints overlapping an inclusive date range as (number, start, end).
+    pub fn sprints_between(
+        &self,
+        first: NaiveDate,
+        last: NaiveDate,
+    ) -> Vec<(u32, NaiveDate, NaiveDate)> {
+        let Some(sprints) = &self.sprints else {
+            return Vec::new();
+        };
+        let length = sprints.length as i64;
+        let first_index = (first - sprints.start).num_days().div_euclid(length).max(0);
+        (first_index..)
+            .map(|index| {
+                let start = sprints.start + chrono::Duration::days(index * length);
+                (
+                    index as u32 + 1,
+                    start,
+                    start + chrono::Duration::days(length - 1),
+                )
+            })
+            .take_while(|&(_, start, _)| start <= last)
+            .collect()
+    }
+}
+
+/// Parse one event entry: `date`, or `start` and `end`, plus an optional `name`.
+fn parse_event(entry: &Value, kind: EventKind) -> Result<ProjectEvent, String> {
+    let Value::Table(entry) = entry else {
+        return Err("Invalid project calendar: events must be tables".to_string());
+    };
+    let (start, end) = match (
+        date_field(entry, "date")?,
+        date_field(entry, "start")?,
+        date_field(entry, "end")?,
+    ) {
+        (Some(date), None, None) => (date, date),
+        (None, Some(start), Some(end)) if start <= end => (start, end),
+        (None, Some(start), Some(end)) => {
+            return Err(format!(
+                "Invalid project calendar: end {} is before start {}",
+                end, start
+            ));
+        }
+        _ => {
+            return Err(
+                "Invalid project calendar: events need `date` or `start` and `end`".to_string(),
+            );
+        }
+    };
+    Ok(ProjectEvent {
+        kind,
+        name: string_field(entry, "name").unwrap_or_else(|| kind.default_name().to_string()),
+        start,
+        end,
+    })
+}
+
+/// Read a date given as a TOML date (2026-11-03) or a string ("2026-11-03").
+fn date_field(table: &Table, key: &str) -> Result<Option<NaiveDate>, String> {
+    let text = match table.get(key) {
+        None => return Ok(None),
+        Some(Value::String(text)) => text.clone(),
+        Some(Value::Datetime(date)) => date.to_string(),
+        Some(value) => return Err(format!("Invalid project calendar date: {}", value)),
+    };
+    NaiveDate::parse_from_str(&text, "%Y-%m-%d")
+        .map(Some)
+        .map_err(|_| format!("Invalid project calendar date: {}", text))
+}
+
+/// Read an optional string field.
+fn string_field(table: &Table, key: &str) -> Option<String> {
+    table.get(key).and_then(Value::as_str).map(str::to_string)
+}
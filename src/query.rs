@@ -0,0 +1,239 @@
+//! Calendar search queries such as "fri 13" or "last mon of may".
+
+use std::fmt;
+
+use chrono::{Month, Weekday};
+
+use crate::formatter::parse_month;
+use crate::types::CalContext;
+
+/// Example queries shown in error messages.
+const QUERY_EXAMPLES: &str = "'fri 13', 'last mon of may', '29 feb', 'months starting on sunday'";
+
+/// Position of a weekday within a month.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Nth {
+    /// 1-based occurrence (1..=5).
+    Number(u32),
+    /// Last occurrence.
+    Last,
+}
+
+/// Parsed calendar search query.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Query {
+    /// Weekday falling on a day of month ("fri 13").
+    WeekdayOnDay { weekday: Weekday, day: u32 },
+    /// N-th weekday of a month or of every month ("last mon of may").
+    NthWeekday {
+        nth: Nth,
+        weekday: Weekday,
+        month: Option<u32>,
+    },
+    /// Fixed day of a month ("29 feb").
+    DayOfMonth { day: u32, month: u32 },
+    /// Months starting on a weekday ("months starting on sunday").
+    MonthsStarting(Weekday),
+    /// Months ending on a weekday ("months ending on friday").
+    MonthsEnding(Weekday),
+}
+
+/// Single search result.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Match {
+    /// Matching date.
+    Day(i32, u32, u32),
+    /// Matching month.
+    Month(i32, u32),
+}
+
+impl Query {
+    /// Parse query text (case-insensitive, English weekday names).
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let lower = s.to_lowercase();
+        let words: Vec<&str> = lower
+            .split_whitespace()
+            .filter(|w| !matches!(*w, "the" | "on" | "that" | "every" | "each"))
+            .collect();
+        let unrecognized = || format!("Unrecognized query: '{}' (try {})", s, QUERY_EXAMPLES);
+
+        match words.as_slice() {
+            [months, kind, weekday] if matches!(*months, "month" | "months") => {
+                let weekday = parse_weekday(weekday).ok_or_else(unrecognized)?;
+                match *kind {
+                    "start" | "starts" | "starting" | "begin" | "begins" | "beginning" => {
+                        Ok(Query::MonthsStarting(weekday))
+                    }
+                    "end" | "ends" | "ending" => Ok(Query::MonthsEnding(weekday)),
+                    _ => Err(unrecognized()),
+                }
+            }
+            [nth, weekday, rest @ ..]
+                if parse_nth(nth).is_some() && parse_weekday(weekday).is_some() =>
+            {
+                let month = match rest {
+                    [] | ["of" | "in", "month"] => None,
+                    ["of" | "in", month] => Some(parse_month(month).ok_or_else(unrecognized)?),
+                    _ => return Err(unrecognized()),
+                };
+                Ok(Query::NthWeekday {
+                    nth: parse_nth(nth).unwrap(),
+                    weekday: parse_weekday(weekday).unwrap(),
+                    month,
+                })
+            }
+            [a, b] => {
+                let (day_a, day_b) = (parse_day(a), parse_day(b));
+                let weekday_day = match (parse_weekday(a), parse_weekday(b)) {
+                    (Some(weekday), _) => day_b.map(|day| (weekday, day)),
+                    (_, Some(weekday)) => day_a.map(|day| (weekday, day)),
+                    _ => None,
+                };
+                if let Some((weekday, day)) = weekday_day {
+                    return Ok(Query::WeekdayOnDay { weekday, day });
+                }
+                let (day, month) = match (day_a, day_b) {
+                    (Some(day), _) => (day, parse_month(b).ok_or_else(unrecognized)?),
+                    (None, Some(day)) => (day, parse_month(a).ok_or_else(unrecognized)?),
+                    (None, None) => return Err(unrecognized()),
+                };
+                Ok(Query::DayOfMonth { day, month })
+            }
+            _ => Err(unrecognized()),
+        }
+    }
+
+    /// Whether results are whole months rather than dates.
+    pub fn is_month_query(&self) -> bool {
+        matches!(self, Query::MonthsStarting(_) | Query::MonthsEnding(_))
+    }
+
+    /// Days of a month matching the query (for month queries: the first or last day).
+    pub fn days_in(&self, ctx: &CalContext, year: i32, month: u32) -> Vec<u32> {
+        let last_day = ctx.days_in_month(year, month);
+        let mut days = (1..=last_day).filter(|&day| !ctx.is_reform_gap(year, month, day));
+        match *self {
+            Query::WeekdayOnDay { weekday, day } => days
+                .filter(|&d| d == day && ctx.weekday(year, month, d) == weekday)
+                .collect(),
+            Query::NthWeekday {
+                nth,
+                weekday,
+                month: wanted,
+            } => {
+                if wanted.is_some_and(|m| m != month) {
+                    return Vec::new();
+                }
+                let candidates: Vec<u32> = days
+                    .filter(|&d| ctx.weekday(year, month, d) == weekday)
+                    .collect();
+                let found = match nth {
+                    Nth::Number(n) => candidates.get(n as usize - 1),
+                    Nth::Last => candidates.last(),
+                };
+                found.copied().into_iter().collect()
+            }
+            Query::DayOfMonth { day, month: wanted } => {
+                days.filter(|&d| wanted == month && d == day).collect()
+            }
+            Query::MonthsStarting(weekday) => days
+                .take(1)
+                .filter(|&d| ctx.weekday(year, month, d) == weekday)
+                .collect(),
+            Query::MonthsEnding(weekday) => days
+                .next_back()
+                .filter(|&d| ctx.weekday(year, month, d) == weekday)
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    /// Check if a date matches the query.
+    pub fn matches(&self, ctx: &CalContext, year: i32, month: u32, day: u32) -> bool {
+        self.days_in(ctx, year, month).contains(&day)
+    }
+
+    /// Find all matches in a month.
+    pub fn find_in_month(&self, ctx: &CalContext, year: i32, month: u32) -> Vec<Match> {
+        let days = self.days_in(ctx, year, month);
+        if self.is_month_query() {
+            if days.is_empty() {
+                Vec::new()
+            } else {
+                vec![Match::Month(year, month)]
+            }
+        } else {
+            days.into_iter()
+                .map(|day| Match::Day(year, month, day))
+                .collect()
+        }
+    }
+
+    /// Find all matches in an inclusive range of years.
+    pub fn find(&self, ctx: &CalContext, first_year: i32, last_year: i32) -> Vec<Match> {
+        (first_year..=last_year)
+            .flat_map(|year| (1..=12).map(move |month| (year, month)))
+            .flat_map(|(year, month)| self.find_in_month(ctx, year, month))
+            .collect()
+    }
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let month_abbr = |m: u32| &Month::try_from(m as u8).unwrap().name()[..3];
+        match *self {
+            Query::WeekdayOnDay { weekday, day } => write!(f, "{} {}", weekday, day),
+            Query::NthWeekday {
+                nth,
+                weekday,
+                month,
+            } => {
+                match nth {
+                    Nth::Number(n) => write!(
+                        f,
+                        "{} {}",
+                        ["1st", "2nd", "3rd", "4th", "5th"][n as usize - 1],
+                        weekday
+                    )?,
+                    Nth::Last => write!(f, "last {}", weekday)?,
+                }
+                match month {
+                    Some(m) => write!(f, " of {}", month_abbr(m)),
+                    None => write!(f, " of every month"),
+                }
+            }
+            Query::DayOfMonth { day, month } => write!(f, "{} {}", day, month_abbr(month)),
+            Query::MonthsStarting(weekday) => write!(f, "months starting on {}", weekday),
+            Query::MonthsEnding(weekday) => write!(f, "months ending on {}", weekday),
+        }
+    }
+}
+
+/// Parse weekday name or abbreviation (plural forms allowed: "fridays").
+fn parse_weekday(s: &str) -> Option<Weekday> {
+    s.parse::<Weekday>()
+        .ok()
+        .or_else(|| s.strip_suffix('s')?.parse().ok())
+}
+
+/// Parse ordinal: first..fifth, 1st..5th or last.
+fn parse_nth(s: &str) -> Option<Nth> {
+    match s {
+        "first" | "1st" => Some(Nth::Number(1)),
+        "second" | "2nd" => Some(Nth::Number(2)),
+        "third" | "3rd" => Some(Nth::Number(3)),
+        "fourth" | "4th" => Some(Nth::Number(4)),
+        "fifth" | "5th" => Some(Nth::Number(5)),
+        "last" => Some(Nth::Last),
+        _ => None,
+    }
+}
+
+/// Parse day of month with optional ordinal suffix ("13", "13th").
+fn parse_day(s: &str) -> Option<u32> {
+    let digits = s.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    if digits.len() != s.len() && !matches!(&s[digits.len()..], "st" | "nd" | "rd" | "th") {
+        return None;
+    }
+    digits.parse().ok().filter(|d| (1..=31).contains(d))
+}
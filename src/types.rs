@@ -3,6 +3,14 @@
 use chrono::Weekday;
 use clap::ValueEnum;
 
+use crate::astro::Location;
+use crate::clock::Clock;
+use crate::colors::Palette;
+use crate::error::CalError;
+use crate::project::LazyProject;
+use crate::query::Query;
+use crate::zoneinfo::TimeZone;
+
 /// Calendar reform type determining which calendar system to use.
 #[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
 #[value(rename_all = "lowercase")]
@@ -38,6 +46,181 @@ pub enum WeekType {
     Us,
 }
 
+/// Quarter (1-4) or half (1-2) of a year.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Period {
+    Quarter(u32),
+    Half(u32),
+}
+
+impl Period {
+    /// Period containing a month.
+    pub fn quarter_of(month: u32) -> Self {
+        Period::Quarter((month - 1) / 3 + 1)
+    }
+
+    pub fn first_month(self) -> u32 {
+        match self {
+            Period::Quarter(n) => (n - 1) * 3 + 1,
+            Period::Half(n) => (n - 1) * 6 + 1,
+        }
+    }
+
+    pub fn months(self) -> u32 {
+        match self {
+            Period::Quarter(_) => 3,
+            Period::Half(_) => 6,
+        }
+    }
+
+    /// Title such as "Q3 2026" or "H1 2026".
+    pub fn title(self, year: i32) -> String {
+        match self {
+            Period::Quarter(n) => format!("Q{} {}", n, year),
+            Period::Half(n) => format!("H{} {}", n, year),
+        }
+    }
+}
+
+/// Date selected by positional arguments.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DisplayDate {
+    pub year: i32,
+    pub month: u32,
+    pub day: Option<u32>,
+    /// Only a year was given (the util-linux layout then shows the whole year).
+    pub whole_year: bool,
+    /// Number of months for a range argument ("2026-03..2026-08").
+    pub months: Option<u32>,
+    /// Quarter or half given as "Q3" or "H1".
+    pub period: Option<Period>,
+}
+
+impl DisplayDate {
+    pub fn year(year: i32, month: u32) -> Self {
+        Self {
+            year,
+            month,
+            day: None,
+            whole_year: true,
+            months: None,
+            period: None,
+        }
+    }
+
+    pub fn month(year: i32, month: u32) -> Self {
+        Self {
+            year,
+            month,
+            day: None,
+            whole_year: false,
+            months: None,
+            period: None,
+        }
+    }
+
+    pub fn range(year: i32, month: u32, months: u32) -> Self {
+        Self {
+            year,
+            month,
+            day: None,
+            whole_year: false,
+            months: Some(months),
+            period: None,
+        }
+    }
+
+    pub fn period(year: i32, period: Period) -> Self {
+        Self {
+            year,
+            month: period.first_month(),
+            day: None,
+            whole_year: false,
+            months: Some(period.months()),
+            period: Some(period),
+        }
+    }
+
+    pub fn day(year: i32, month: u32, day: u32) -> Self {
+        Self {
+            year,
+            month,
+            day: Some(day),
+            whole_year: false,
+            months: None,
+            period: None,
+        }
+    }
+}
+
+/// How closely another year's calendar matches a given year.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LayoutMatch {
+    /// All twelve months are identical.
+    Full,
+    /// Only January and February are identical.
+    JanFeb,
+    /// Only January is identical: February differs in length.
+    January,
+    /// Only March through December are identical.
+    MarDec,
+}
+
+/// When to use colorized output.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ColorWhen {
+    /// Color when stdout is a terminal (honours NO_COLOR, CLICOLOR_FORCE, TERM=dumb).
+    Auto,
+    /// Always color, even through pipes and into files.
+    Always,
+    /// Never color.
+    Never,
+}
+
+/// Built-in color theme.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Theme {
+    /// Pick default or solarized-light from the terminal background.
+    Auto,
+    /// Classic bright colors for dark backgrounds.
+    Default,
+    /// No colors, only bold, underline and reverse video.
+    Mono,
+    /// Solarized colors readable on light backgrounds.
+    SolarizedLight,
+    /// Bold, saturated colors.
+    HighContrast,
+    /// Blue/orange palette safe for red-green color blindness.
+    Colorblind,
+}
+
+/// Output layout compatibility.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Compat {
+    /// Native layout with all extras.
+    None,
+    /// Byte-exact util-linux cal layout.
+    UtilLinux,
+}
+
+/// Easter computus for --easter.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Easter {
+    /// Western Easter (Julian computus before the reform).
+    Western,
+    /// Orthodox Easter (Julian computus).
+    Orthodox,
+}
+
+/// Week highlighted by -w.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WeekHighlight {
+    /// The week containing today.
+    Current,
+    /// A week number of the displayed year.
+    Number(u32),
+}
+
 /// Column display mode for multi-month layouts.
 #[derive(Debug, Clone, Copy)]
 pub enum ColumnsMode {
@@ -58,14 +241,27 @@ pub struct CalContext {
     pub julian: bool,
     /// Whether to display ISO week numbers.
     pub week_numbers: bool,
+    /// Week highlighted with -w, as (week-based year, week number).
+    pub highlight_week: Option<(i32, u32)>,
     /// Week numbering system (ISO or US).
     pub week_type: WeekType,
     /// Whether to use ANSI color codes in output.
     pub color: bool,
+    /// Color sequences for calendar elements.
+    pub palette: Palette,
     /// Whether to display days vertically (days in columns instead of rows).
     pub vertical: bool,
+    /// Reproduce the util-linux cal layout byte for byte.
+    pub compat: bool,
     /// Today's date for highlighting.
     pub today: chrono::NaiveDate,
+    /// Clock that "today" was taken from.
+    pub clock: Clock,
+    /// Positional arguments read as a date, or why they are not one; parsed once, since
+    /// only the calendar views need a date.
+    pub date: Result<DisplayDate, CalError>,
+    /// Day given on the command line as (year, month, day), highlighted apart from today.
+    pub selected: Option<(i32, u32, u32)>,
     /// Whether to show year in month headers.
     pub show_year_in_header: bool,
     /// Width of gutter between months in multi-month display.
@@ -74,9 +270,28 @@ pub struct CalContext {
     pub columns: ColumnsMode,
     /// Whether to center the date range when displaying multiple months.
     pub span: bool,
+    /// Whether to mark equinoxes and solstices.
+    pub seasons: bool,
+    /// Location for the sunrise and sunset agenda.
+    pub location: Option<Location>,
+    /// Whether to mark polar days and polar nights at the location.
+    pub polar: bool,
+    /// Time zones from `--tz`; the first one is used for displayed times.
+    pub time_zones: Vec<TimeZone>,
+    /// Whether to mark days on which the UTC offset changes.
+    pub dst: bool,
+    /// Today's date in additional time zones where it differs from `today`.
+    pub zone_todays: Vec<(String, chrono::NaiveDate)>,
+    /// Search query whose matches are highlighted.
+    pub find: Option<Query>,
+    /// Project calendar from .calendar.toml, read when first needed.
+    pub project: LazyProject,
     /// Whether to highlight holidays using isdayoff.ru API.
     #[cfg(feature = "plugins")]
     pub holidays: bool,
+    /// Country code for holidays (None: from the locale).
+    #[cfg(feature = "plugins")]
+    pub country: Option<String>,
 }
 
 /// Calendar data for a single month.
@@ -93,6 +308,14 @@ pub const CELLS_PER_MONTH: usize = 42; // 6 weeks × 7 days
 pub const GUTTER_WIDTH_REGULAR: usize = 2;
 pub const GUTTER_WIDTH_YEAR: usize = 3;
 
+// Month widths: plain, with week numbers, with day-of-year (-j) cells, vertical
+pub const MONTH_WIDTH: usize = 20;
+pub const MONTH_WIDTH_WEEK_NUMBERS: usize = 23;
+pub const MONTH_WIDTH_JULIAN: usize = 27;
+pub const MONTH_WIDTH_VERTICAL: usize = 18;
+// Weekday names in front of vertical months
+pub const WEEKDAY_COLUMN_WIDTH: usize = 4;
+
 // Color is enabled by default for better user experience
 pub const COLOR_ENABLED_BY_DEFAULT: bool = true;
 
@@ -105,6 +328,9 @@ pub const REFORM_LAST_DAY: u32 = 13;
 // ANSI color codes
 pub const COLOR_RESET: &str = "\x1b[0m";
 pub const COLOR_REVERSE: &str = "\x1b[7m";
+pub const COLOR_UNDERLINE: &str = "\x1b[4m";
 pub const COLOR_RED: &str = "\x1b[91m";
 pub const COLOR_TEAL: &str = "\x1b[96m";
 pub const COLOR_SAND_YELLOW: &str = "\x1b[93m";
+pub const COLOR_MAGENTA: &str = "\x1b[95m";
+pub const COLOR_BLACK_ON_YELLOW: &str = "\x1b[30;103m";
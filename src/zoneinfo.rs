@@ -0,0 +1,559 @@
+//! Time zone support using the system zoneinfo database (TZif files, RFC 8536).
+//!
+//! Transitions stored in the file are used directly; instants after the last
+//! stored transition follow the POSIX TZ rule from the file footer.
+
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, Utc};
+
+/// Default location of the zoneinfo database.
+const ZONEINFO_DIR: &str = "/usr/share/zoneinfo";
+
+/// Zone of the system when `TZ` is not set.
+const LOCALTIME_PATH: &str = "/etc/localtime";
+
+/// Seconds in an hour.
+const HOUR: i32 = 3600;
+
+/// Local time type: UTC offset, DST flag and abbreviation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalType {
+    /// Offset from UTC in seconds (east positive).
+    pub utc_offset: i32,
+    /// Whether this type is daylight saving time.
+    pub is_dst: bool,
+    /// Time zone abbreviation (e.g. "CEST").
+    pub abbreviation: String,
+}
+
+impl LocalType {
+    /// Format the UTC offset as `+01:00`.
+    pub fn offset_string(&self) -> String {
+        let sign = if self.utc_offset < 0 { '-' } else { '+' };
+        let minutes = self.utc_offset.abs() / 60;
+        format!("{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+    }
+}
+
+/// Change of UTC offset at a specific instant.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OffsetChange {
+    /// Instant of the change (Unix seconds).
+    pub at: i64,
+    /// Local time type before the change.
+    pub before: LocalType,
+    /// Local time type after the change.
+    pub after: LocalType,
+}
+
+impl OffsetChange {
+    /// Wall-clock time at which the change happens (in the old offset).
+    pub fn local_time(&self) -> DateTime<FixedOffset> {
+        to_fixed(self.at, self.before.utc_offset)
+    }
+}
+
+/// Day of a POSIX TZ rule.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RuleDay {
+    /// `Jn`: day 1..=365, February 29 is never counted.
+    Julian1(u32),
+    /// `n`: zero-based day of year 0..=365, counting February 29.
+    Julian0(u32),
+    /// `Mm.w.d`: day `d` (0 = Sunday) of week `w` (5 = last) of month `m`.
+    MonthWeekDay(u32, u32, u32),
+}
+
+/// Rule date with the local time of the transition in seconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct RuleDate {
+    day: RuleDay,
+    time: i32,
+}
+
+/// Parsed POSIX TZ string (e.g. `CET-1CEST,M3.5.0,M10.5.0/3`).
+#[derive(Debug, Clone, PartialEq)]
+struct PosixTz {
+    std: LocalType,
+    dst: Option<(LocalType, RuleDate, RuleDate)>,
+}
+
+/// Time zone loaded from the zoneinfo database.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeZone {
+    /// Zone name (e.g. "Europe/Berlin").
+    pub name: String,
+    /// Transition instants (Unix seconds) with the index of the new local type.
+    transitions: Vec<(i64, usize)>,
+    /// Local time types referenced by transitions.
+    types: Vec<LocalType>,
+    /// Rule for instants after the last transition.
+    rule: Option<PosixTz>,
+}
+
+impl TimeZone {
+    /// Load a zone by name (e.g. "Europe/Berlin") from the zoneinfo database.
+    ///
+    /// The database directory can be overridden with the `TZDIR` environment variable.
+    /// Names that are not found are tried as POSIX TZ strings (e.g. "EST5EDT").
+    pub fn load(name: &str) -> Result<Self, String> {
+        let name = name.strip_prefix(':').unwrap_or(name);
+        let path = if name.starts_with('/') {
+            std::path::PathBuf::from(name)
+        } else {
+            if name.split('/').any(|part| part == "..") {
+                return Err(format!("Invalid time zone: {}", name));
+            }
+            let dir = std::env::var("TZDIR").unwrap_or_else(|_| ZONEINFO_DIR.to_string());
+            std::path::Path::new(&dir).join(name)
+        };
+
+        match std::fs::read(&path) {
+            Ok(data) => TimeZone::parse(name, &data),
+            Err(_) => parse_posix_tz(name)
+                .map(|rule| TimeZone {
+                    name: name.to_string(),
+                    transitions: Vec::new(),
+                    types: vec![rule.std.clone()],
+                    rule: Some(rule),
+                })
+                .ok_or_else(|| format!("Unknown time zone: {}", name)),
+        }
+    }
+
+    /// Load the local zone from `TZ` or `/etc/localtime`, falling back to UTC.
+    pub fn local() -> Self {
+        if let Ok(tz) = std::env::var("TZ")
+            && !tz.is_empty()
+            && let Ok(zone) = TimeZone::load(&tz)
+        {
+            return zone;
+        }
+        std::fs::read(LOCALTIME_PATH)
+            .ok()
+            .and_then(|data| TimeZone::parse(&local_zone_name(), &data).ok())
+            .unwrap_or_else(TimeZone::utc)
+    }
+
+    /// Coordinated Universal Time.
+    pub fn utc() -> Self {
+        TimeZone {
+            name: "UTC".to_string(),
+            transitions: Vec::new(),
+            types: vec![LocalType {
+                utc_offset: 0,
+                is_dst: false,
+                abbreviation: "UTC".to_string(),
+            }],
+            rule: None,
+        }
+    }
+
+    /// Parse TZif data (versions 1-4).
+    pub fn parse(name: &str, data: &[u8]) -> Result<Self, String> {
+        let invalid = || format!("Invalid zoneinfo data for {}", name);
+
+        let header = TzifHeader::parse(data).ok_or_else(invalid)?;
+        // Version 2+ files repeat the data with 64-bit times after the v1 block
+        let (header, body, time_size) = if header.version >= b'2' {
+            let v2_start = 44 + header.block_len(4);
+            let v2_header =
+                TzifHeader::parse(data.get(v2_start..).ok_or_else(invalid)?).ok_or_else(invalid)?;
+            (v2_header, v2_start + 44, 8)
+        } else {
+            (header, 44, 4)
+        };
+
+        let mut reader = Reader { data, pos: body };
+        let mut times = Vec::with_capacity(header.timecnt);
+        for _ in 0..header.timecnt {
+            let time = if time_size == 8 {
+                reader.i64().ok_or_else(invalid)?
+            } else {
+                reader.i32().ok_or_else(invalid)? as i64
+            };
+            times.push(time);
+        }
+        let mut indices = Vec::with_capacity(header.timecnt);
+        for _ in 0..header.timecnt {
+            let idx = reader.u8().ok_or_else(invalid)? as usize;
+            if idx >= header.typecnt {
+                return Err(invalid());
+            }
+            indices.push(idx);
+        }
+
+        let mut raw_types = Vec::with_capacity(header.typecnt);
+        for _ in 0..header.typecnt {
+            let utc_offset = reader.i32().ok_or_else(invalid)?;
+            let is_dst = reader.u8().ok_or_else(invalid)? != 0;
+            let abbr_idx = reader.u8().ok_or_else(invalid)? as usize;
+            raw_types.push((utc_offset, is_dst, abbr_idx));
+        }
+        let chars = reader.bytes(header.charcnt).ok_or_else(invalid)?;
+        let types = raw_types
+            .into_iter()
+            .map(|(utc_offset, is_dst, abbr_idx)| {
+                let abbr = chars.get(abbr_idx..).unwrap_or_default();
+                let end = abbr.iter().position(|&b| b == 0).unwrap_or(abbr.len());
+                LocalType {
+                    utc_offset,
+                    is_dst,
+                    abbreviation: String::from_utf8_lossy(&abbr[..end]).into_owned(),
+                }
+            })
+            .collect::<Vec<_>>();
+        if types.is_empty() {
+            return Err(invalid());
+        }
+
+        // Footer with the POSIX TZ rule (version 2+ only)
+        reader.skip(header.leapcnt * (time_size + 4) + header.isstdcnt + header.isutcnt);
+        let rule = if time_size == 8 {
+            reader
+                .rest()
+                .strip_prefix(b"\n")
+                .and_then(|footer| footer.split(|&b| b == b'\n').next())
+                .and_then(|footer| std::str::from_utf8(footer).ok())
+                .filter(|footer| !footer.is_empty())
+                .and_then(parse_posix_tz)
+        } else {
+            None
+        };
+
+        Ok(TimeZone {
+            name: name.to_string(),
+            transitions: times.into_iter().zip(indices).collect(),
+            types,
+            rule,
+        })
+    }
+
+    /// Local time type in effect at an instant (Unix seconds).
+    pub fn local_type_at(&self, at: i64) -> LocalType {
+        let n = self.transitions.partition_point(|&(time, _)| time <= at);
+        if n == self.transitions.len()
+            && let Some(rule) = &self.rule
+        {
+            return rule.local_type_at(at);
+        }
+        match n {
+            0 => self.types[0].clone(),
+            n => self.types[self.transitions[n - 1].1].clone(),
+        }
+    }
+
+    /// Convert a UTC instant to local time in this zone.
+    pub fn to_local(&self, instant: DateTime<Utc>) -> DateTime<FixedOffset> {
+        let at = instant.timestamp();
+        to_fixed(at, self.local_type_at(at).utc_offset)
+    }
+
+    /// All changes of the UTC offset in `[start, end)` (Unix seconds).
+    pub fn offset_changes(&self, start: i64, end: i64) -> Vec<OffsetChange> {
+        let mut instants: Vec<(i64, LocalType)> = self
+            .transitions
+            .iter()
+            .filter(|&&(at, _)| at >= start && at < end)
+            .map(|&(at, idx)| (at, self.types[idx].clone()))
+            .collect();
+
+        if let Some(rule) = &self.rule {
+            let last = self.transitions.last().map_or(i64::MIN, |&(at, _)| at);
+            let first_year = year_of(start.max(last)) - 1;
+            for year in first_year..=year_of(end) + 1 {
+                instants.extend(
+                    rule.transitions(year)
+                        .into_iter()
+                        .filter(|&(at, _)| at > last && at >= start && at < end),
+                );
+            }
+            instants.sort_by_key(|&(at, _)| at);
+        }
+
+        let mut current = self.local_type_at(start - 1);
+        let mut changes = Vec::new();
+        for (at, after) in instants {
+            if after.utc_offset != current.utc_offset {
+                changes.push(OffsetChange {
+                    at,
+                    before: current.clone(),
+                    after: after.clone(),
+                });
+            }
+            current = after;
+        }
+        changes
+    }
+}
+
+impl PosixTz {
+    /// Local time type at an instant according to the rule.
+    fn local_type_at(&self, at: i64) -> LocalType {
+        let Some((dst, _, _)) = &self.dst else {
+            return self.std.clone();
+        };
+        let [(start, _), (end, _)] = self.transitions(year_of(at))[..] else {
+            return self.std.clone();
+        };
+        let in_dst = if start < end {
+            at >= start && at < end
+        } else {
+            // Southern hemisphere: DST spans the new year
+            !(at >= end && at < start)
+        };
+        if in_dst {
+            dst.clone()
+        } else {
+            self.std.clone()
+        }
+    }
+
+    /// Start and end of DST in a year (Unix seconds) with the new local type.
+    fn transitions(&self, year: i32) -> Vec<(i64, LocalType)> {
+        let Some((dst, start, end)) = &self.dst else {
+            return Vec::new();
+        };
+        match (start.local_seconds(year), end.local_seconds(year)) {
+            (Some(start), Some(end)) => vec![
+                (start - self.std.utc_offset as i64, dst.clone()),
+                (end - dst.utc_offset as i64, self.std.clone()),
+            ],
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl RuleDate {
+    /// Local wall-clock seconds since the Unix epoch of the rule date in a year.
+    fn local_seconds(&self, year: i32) -> Option<i64> {
+        let date = match self.day {
+            RuleDay::Julian1(n) => {
+                let jan1 = NaiveDate::from_ymd_opt(year, 1, 1)?;
+                let leap = NaiveDate::from_ymd_opt(year, 2, 29).is_some();
+                let ordinal0 = if leap && n >= 60 { n } else { n - 1 };
+                jan1 + chrono::Duration::days(ordinal0 as i64)
+            }
+            RuleDay::Julian0(n) => {
+                NaiveDate::from_ymd_opt(year, 1, 1)? + chrono::Duration::days(n as i64)
+            }
+            RuleDay::MonthWeekDay(month, week, weekday) => {
+                let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+                let first_wd = first.weekday().num_days_from_sunday();
+                let mut day = 1 + (weekday + 7 - first_wd) % 7 + (week - 1) * 7;
+                while NaiveDate::from_ymd_opt(year, month, day).is_none() {
+                    day -= 7;
+                }
+                NaiveDate::from_ymd_opt(year, month, day)?
+            }
+        };
+        let midnight = date.and_hms_opt(0, 0, 0)?.and_utc().timestamp();
+        Some(midnight + self.time as i64)
+    }
+}
+
+/// Fixed-size TZif header.
+struct TzifHeader {
+    version: u8,
+    isutcnt: usize,
+    isstdcnt: usize,
+    leapcnt: usize,
+    timecnt: usize,
+    typecnt: usize,
+    charcnt: usize,
+}
+
+impl TzifHeader {
+    fn parse(data: &[u8]) -> Option<Self> {
+        if data.len() < 44 || &data[..4] != b"TZif" {
+            return None;
+        }
+        let count = |i: usize| {
+            let off = 20 + i * 4;
+            u32::from_be_bytes(data[off..off + 4].try_into().unwrap()) as usize
+        };
+        Some(TzifHeader {
+            version: data[4],
+            isutcnt: count(0),
+            isstdcnt: count(1),
+            leapcnt: count(2),
+            timecnt: count(3),
+            typecnt: count(4),
+            charcnt: count(5),
+        })
+    }
+
+    /// Length of the data block following the header.
+    fn block_len(&self, time_size: usize) -> usize {
+        self.timecnt * (time_size + 1)
+            + self.typecnt * 6
+            + self.charcnt
+            + self.leapcnt * (time_size + 4)
+            + self.isstdcnt
+            + self.isutcnt
+    }
+}
+
+/// Big-endian cursor over TZif data.
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let slice = self.data.get(self.pos..self.pos + len)?;
+        self.pos += len;
+        Some(slice)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.bytes(1).map(|b| b[0])
+    }
+
+    fn i32(&mut self) -> Option<i32> {
+        self.bytes(4)
+            .map(|b| i32::from_be_bytes(b.try_into().unwrap()))
+    }
+
+    fn i64(&mut self) -> Option<i64> {
+        self.bytes(8)
+            .map(|b| i64::from_be_bytes(b.try_into().unwrap()))
+    }
+
+    fn skip(&mut self, len: usize) {
+        self.pos += len;
+    }
+
+    fn rest(&self) -> &'a [u8] {
+        self.data.get(self.pos..).unwrap_or_default()
+    }
+}
+
+/// Parse a POSIX TZ string such as `EST5EDT,M3.2.0,M11.1.0`.
+fn parse_posix_tz(s: &str) -> Option<PosixTz> {
+    let mut rest = s;
+    let std_name = take_zone_name(&mut rest)?;
+    let std_offset = -take_offset(&mut rest)?;
+    let std = LocalType {
+        utc_offset: std_offset,
+        is_dst: false,
+        abbreviation: std_name,
+    };
+    if rest.is_empty() {
+        return Some(PosixTz { std, dst: None });
+    }
+
+    let dst_name = take_zone_name(&mut rest)?;
+    let dst_offset = if rest.is_empty() || rest.starts_with(',') {
+        std_offset + HOUR
+    } else {
+        -take_offset(&mut rest)?
+    };
+    let dst = LocalType {
+        utc_offset: dst_offset,
+        is_dst: true,
+        abbreviation: dst_name,
+    };
+
+    // Without explicit rules use the US rules, like glibc
+    let rules = rest.strip_prefix(',').unwrap_or("M3.2.0,M11.1.0");
+    let (start, end) = rules.split_once(',')?;
+    Some(PosixTz {
+        std,
+        dst: Some((dst, parse_rule_date(start)?, parse_rule_date(end)?)),
+    })
+}
+
+/// Take a zone abbreviation: alphabetic (`CET`) or quoted (`<+03>`).
+fn take_zone_name(s: &mut &str) -> Option<String> {
+    let (name, rest) = if let Some(quoted) = s.strip_prefix('<') {
+        let end = quoted.find('>')?;
+        (&quoted[..end], &quoted[end + 1..])
+    } else {
+        let end = s
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(s.len());
+        (&s[..end], &s[end..])
+    };
+    if name.len() < 3 {
+        return None;
+    }
+    *s = rest;
+    Some(name.to_string())
+}
+
+/// Take an offset or time `[+-]hh[:mm[:ss]]` in seconds.
+fn take_offset(s: &mut &str) -> Option<i32> {
+    let end = s
+        .find(|c: char| !(c.is_ascii_digit() || matches!(c, ':' | '+' | '-')))
+        .unwrap_or(s.len());
+    let (value, rest) = s.split_at(end);
+    *s = rest;
+    parse_time(value)
+}
+
+/// Parse `[+-]hh[:mm[:ss]]` to seconds.
+fn parse_time(s: &str) -> Option<i32> {
+    let (sign, digits) = match s.as_bytes().first()? {
+        b'-' => (-1, &s[1..]),
+        b'+' => (1, &s[1..]),
+        _ => (1, s),
+    };
+    let mut seconds = 0;
+    let mut unit = HOUR;
+    for part in digits.split(':') {
+        if unit == 0 {
+            return None;
+        }
+        seconds += part.parse::<i32>().ok()? * unit;
+        unit /= 60;
+    }
+    Some(sign * seconds)
+}
+
+/// Parse a rule date such as `M3.5.0/3`, `J60` or `59/-1`.
+fn parse_rule_date(s: &str) -> Option<RuleDate> {
+    let (day, time) = match s.split_once('/') {
+        Some((day, time)) => (day, parse_time(time)?),
+        None => (s, 2 * HOUR),
+    };
+    let day = if let Some(n) = day.strip_prefix('J') {
+        RuleDay::Julian1(n.parse().ok().filter(|n| (1..=365).contains(n))?)
+    } else if let Some(mwd) = day.strip_prefix('M') {
+        let mut parts = mwd.split('.').map(|p| p.parse::<u32>().ok());
+        let (m, w, d) = (parts.next()??, parts.next()??, parts.next()??);
+        if !(1..=12).contains(&m) || !(1..=5).contains(&w) || d > 6 {
+            return None;
+        }
+        RuleDay::MonthWeekDay(m, w, d)
+    } else {
+        RuleDay::Julian0(day.parse().ok().filter(|n| *n <= 365)?)
+    };
+    Some(RuleDate { day, time })
+}
+
+/// Guess the zone name of `/etc/localtime` from its symlink target.
+fn local_zone_name() -> String {
+    std::fs::read_link(LOCALTIME_PATH)
+        .ok()
+        .and_then(|target| {
+            let target = target.to_string_lossy().into_owned();
+            target
+                .split_once("zoneinfo/")
+                .map(|(_, name)| name.to_string())
+        })
+        .unwrap_or_else(|| "localtime".to_string())
+}
+
+/// UTC year of an instant.
+fn year_of(at: i64) -> i32 {
+    DateTime::from_timestamp(at, 0).map_or(1970, |dt| dt.year())
+}
+
+/// Convert Unix seconds to a fixed-offset date-time.
+fn to_fixed(at: i64, utc_offset: i32) -> DateTime<FixedOffset> {
+    let offset = FixedOffset::east_opt(utc_offset).unwrap_or(FixedOffset::east_opt(0).unwrap());
+    DateTime::from_timestamp(at, 0)
+        .unwrap_or_default()
+        .with_timezone(&offset)
+}
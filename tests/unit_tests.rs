@@ -2,14 +2,20 @@
 
 use std::io::IsTerminal;
 
-use chrono::{Datelike, Weekday};
+use chrono::{Datelike, Locale, Weekday};
 use unicode_width::UnicodeWidthStr;
 
-use cal::args::{Args, get_display_date};
+use cal::args::{Args, get_display_date, parse_display_date};
+use cal::clock::Clock;
+use cal::colors::Palette;
 use cal::formatter::{
     format_month_grid, format_month_header, format_weekday_headers, get_weekday_order, parse_month,
+    parse_month_in,
+};
+use cal::project::LazyProject;
+use cal::types::{
+    CalContext, ColumnsMode, DisplayDate, MonthData, Period, ReformType, WeekHighlight, WeekType,
 };
-use cal::types::{CalContext, ColumnsMode, MonthData, ReformType, WeekType};
 
 use clap::Parser;
 
@@ -23,19 +29,40 @@ fn base_context() -> CalContext {
         week_start: Weekday::Mon,
         julian: false,
         week_numbers: false,
+        highlight_week: None,
         week_type: WeekType::Iso,
         color: false,
+        palette: Palette::default(),
         vertical: false,
+        compat: false,
         today: chrono::NaiveDate::from_ymd_opt(2026, 2, 18).unwrap(),
+        clock: Clock::at_date(chrono::NaiveDate::from_ymd_opt(2026, 2, 18).unwrap(), None),
+        date: Ok(DisplayDate::month(2026, 2)),
+        selected: None,
         show_year_in_header: true,
         gutter_width: 2,
         columns: ColumnsMode::Auto,
         span: false,
+        seasons: false,
+        location: None,
+        polar: false,
+        time_zones: Vec::new(),
+        dst: false,
+        zone_todays: Vec::new(),
+        find: None,
+        project: LazyProject::default(),
         #[cfg(feature = "plugins")]
         holidays: false,
+        #[cfg(feature = "plugins")]
+        country: None,
     }
 }
 
+/// Context built from the arguments, for parsing their positional dates.
+fn context_for(args: &Args) -> CalContext {
+    CalContext::new(args).unwrap()
+}
+
 fn julian_context() -> CalContext {
     CalContext {
         reform_year: ReformType::Julian.reform_year(),
@@ -158,15 +185,20 @@ mod first_day_of_month {
     #[test]
     fn september_1752_reform() {
         let ctx = base_context();
-        assert_eq!(ctx.first_day_of_month(1752, 9), Weekday::Fri);
+        // 1 September 1752 was still Julian: a Tuesday
+        assert_eq!(ctx.first_day_of_month(1752, 9), Weekday::Tue);
+        assert_eq!(ctx.first_day_of_month(1752, 8), Weekday::Sat);
+        assert_eq!(ctx.first_day_of_month(1752, 10), Weekday::Sun);
     }
 
     #[test]
     fn julian_calendar_dates() {
         let ctx = julian_context();
         // Under pure Julian, 1900 is a leap year (divisible by 4).
-        // Julian Zeller for 1 March 1900: Monday
-        assert_eq!(ctx.first_day_of_month(1900, 3), Weekday::Mon);
+        // Julian 1 March 1900 is Gregorian 14 March 1900: Wednesday
+        assert_eq!(ctx.first_day_of_month(1900, 3), Weekday::Wed);
+        // Julian 1 January 2026 is Gregorian 14 January 2026: Wednesday
+        assert_eq!(ctx.first_day_of_month(2026, 1), Weekday::Wed);
         // Julian and Gregorian agree for dates well after reform.
         // Verify that Julian context still computes early dates without panic.
         let _ = ctx.first_day_of_month(500, 6);
@@ -281,6 +313,8 @@ mod day_of_year {
 
 mod week_numbers {
     use super::*;
+    use cal::args::attach_short_values;
+    use clap::CommandFactory;
 
     #[test]
     fn iso_week_jan_1() {
@@ -314,6 +348,100 @@ mod week_numbers {
         let wk = ctx.week_number(2024, 7, 1);
         assert!(wk > 25);
     }
+
+    #[test]
+    fn optional_week_to_highlight() {
+        let args = Args::parse_from(["cal", "-w"]);
+        assert_eq!(args.week_numbers, Some(WeekHighlight::Current));
+        let args = Args::parse_from(["cal", "-w=10", "2026"]);
+        assert_eq!(args.week_numbers, Some(WeekHighlight::Number(10)));
+        let args = Args::parse_from(["cal", "--week-numbers=53"]);
+        assert!(CalContext::new(&args).unwrap().week_numbers);
+        assert!(Args::try_parse_from(["cal", "--week-numbers=54"]).is_err());
+        assert!(Args::parse_from(["cal"]).week_numbers.is_none());
+    }
+
+    #[test]
+    fn highlighted_week_row() {
+        let ctx = CalContext {
+            week_numbers: true,
+            highlight_week: Some((2026, 8)),
+            color: true,
+            today: chrono::NaiveDate::from_ymd_opt(2000, 1, 1).unwrap(),
+            ..base_context()
+        };
+        let week = ctx.palette.weekhighlight.clone();
+        let grid = format_month_grid(&ctx, &MonthData::new(&ctx, 2026, 2));
+        // Week 8 is February 16-22: the number and all seven days
+        let row = grid.iter().find(|line| line.contains("16")).unwrap();
+        assert_eq!(row.matches(week.as_str()).count(), 8);
+        assert!(
+            grid.iter()
+                .filter(|line| line.contains(week.as_str()))
+                .count()
+                == 1
+        );
+    }
+
+    #[test]
+    fn highlighted_week_follows_week_year() {
+        // ISO week 1 of 2027 starts on January 4; the row of January 1-3 is week 53 of 2026
+        let ctx = CalContext {
+            week_numbers: true,
+            highlight_week: Some((2027, 1)),
+            color: true,
+            today: chrono::NaiveDate::from_ymd_opt(2000, 1, 1).unwrap(),
+            ..base_context()
+        };
+        let week = ctx.palette.weekhighlight.clone();
+        let grid = format_month_grid(&ctx, &MonthData::new(&ctx, 2027, 1));
+        assert!(!grid[2].contains(week.as_str()));
+        assert!(grid[3].contains(week.as_str()));
+    }
+
+    #[test]
+    fn highlighted_week_keeps_today_distinct() {
+        let ctx = CalContext {
+            week_numbers: true,
+            highlight_week: Some((2026, 8)),
+            color: true,
+            today: chrono::NaiveDate::from_ymd_opt(2026, 2, 18).unwrap(),
+            ..base_context()
+        };
+        let grid = format_month_grid(&ctx, &MonthData::new(&ctx, 2026, 2));
+        let row = grid.iter().find(|line| line.contains("16")).unwrap();
+        assert_ne!(ctx.palette.today, ctx.palette.weekhighlight);
+        assert_eq!(row.matches(ctx.palette.today.as_str()).count(), 1);
+        assert_eq!(row.matches(ctx.palette.weekhighlight.as_str()).count(), 7);
+    }
+
+    #[test]
+    fn attached_week_number() {
+        let argv = |args: &[&str]| -> Vec<String> {
+            let argv = args.iter().map(std::ffi::OsString::from).collect();
+            attach_short_values(argv, &Args::command())
+                .iter()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect()
+        };
+        assert_eq!(argv(&["cal", "-w10", "2026"]), ["cal", "-w=10", "2026"]);
+        assert_eq!(argv(&["cal", "-jw5"]), ["cal", "-jw=5"]);
+        assert_eq!(
+            argv(&["cal", "-w", "-wy", "-w=3"]),
+            ["cal", "-w", "-wy", "-w=3"]
+        );
+        assert_eq!(argv(&["cal", "--", "-w1"]), ["cal", "--", "-w1"]);
+    }
+
+    #[test]
+    fn highlighted_week_is_set_by_the_context() {
+        let args = Args::parse_from(["cal", "-w=10", "2027"]);
+        assert_eq!(context_for(&args).highlight_week, Some((2027, 10)));
+        let args = Args::parse_from(["cal", "-w", "--today", "2026-02-18"]);
+        assert_eq!(context_for(&args).highlight_week, Some((2026, 8)));
+        let args = Args::parse_from(["cal"]);
+        assert_eq!(context_for(&args).highlight_week, None);
+    }
 }
 
 // ===========================================================================
@@ -393,6 +521,9 @@ mod month_data {
         }
         assert!(m.days.contains(&Some(14)));
         assert!(m.days.contains(&Some(30)));
+        // Thursday the 14th follows Wednesday the 2nd
+        assert_eq!(m.days[1..4], [Some(1), Some(2), Some(14)]);
+        assert_eq!(m.weekdays[3], Some(Weekday::Thu));
     }
 
     #[test]
@@ -438,12 +569,14 @@ mod month_data {
 
 mod context_creation {
     use super::*;
+    use cal::args::deprecation_warning;
 
     #[test]
     fn default_args() {
         let args = Args::parse_from(["cal"]);
         let ctx = CalContext::new(&args).unwrap();
-        assert_eq!(ctx.week_start, Weekday::Mon);
+        // The week start follows the test environment's locale
+        assert_eq!(ctx.week_start, cal::formatter::locale_week_defaults().0);
         assert!(!ctx.julian);
         assert!(!ctx.week_numbers);
     }
@@ -503,6 +636,34 @@ mod context_creation {
         assert!(!ctx.color);
     }
 
+    #[test]
+    fn color_when() {
+        let args = Args::parse_from(["cal", "--color=always"]);
+        assert!(CalContext::new(&args).unwrap().color);
+
+        let args = Args::parse_from(["cal", "--color=never"]);
+        assert!(!CalContext::new(&args).unwrap().color);
+
+        let args = Args::parse_from(["cal", "--color=auto"]);
+        assert!(CalContext::new(&args).unwrap().color <= std::io::stdout().is_terminal());
+
+        assert!(Args::try_parse_from(["cal", "--color=sometimes"]).is_err());
+    }
+
+    #[test]
+    fn bare_color_keeps_positional_arguments() {
+        let args = Args::parse_from(["cal", "--color", "2", "2026"]);
+        assert_eq!(args.color, Some(None));
+        assert!(
+            deprecation_warning(&args).is_some_and(|warning| warning.contains("--color=never"))
+        );
+        assert!(deprecation_warning(&Args::parse_from(["cal", "--color=never"])).is_none());
+        assert_eq!(
+            get_display_date(&args, &context_for(&args)).unwrap(),
+            (2026, 2, None)
+        );
+    }
+
     #[test]
     fn reform_gregorian() {
         let args = Args::parse_from(["cal", "--reform", "gregorian"]);
@@ -631,6 +792,38 @@ mod parse_month_tests {
         }
     }
 
+    #[test]
+    fn russian_genitive_and_abbreviations() {
+        assert_eq!(parse_month_in("марта", Locale::ru_RU), Some(3));
+        assert_eq!(parse_month_in("Декабря", Locale::ru_RU), Some(12));
+        assert_eq!(parse_month_in("сен", Locale::ru_RU), Some(9));
+        assert_eq!(parse_month_in("мая", Locale::ru_RU), Some(5));
+        assert_eq!(parse_month_in("марта", Locale::en_US), Some(3));
+    }
+
+    #[test]
+    fn names_from_display_locale() {
+        assert_eq!(parse_month_in("Березень", Locale::uk_UA), Some(3));
+        assert_eq!(parse_month_in("березня", Locale::uk_UA), Some(3));
+        assert_eq!(parse_month_in("лют", Locale::uk_UA), Some(2));
+        assert_eq!(parse_month_in("Кастрычнік", Locale::be_BY), Some(10));
+        assert_eq!(parse_month_in("жніўня", Locale::be_BY), Some(8));
+        assert_eq!(parse_month_in("März", Locale::de_DE), Some(3));
+        assert_eq!(parse_month_in("Okt", Locale::de_DE), Some(10));
+        assert_eq!(parse_month_in("Березень", Locale::de_DE), None);
+    }
+
+    #[test]
+    fn accent_insensitive() {
+        assert_eq!(parse_month_in("février", Locale::fr_FR), Some(2));
+        assert_eq!(parse_month_in("fevrier", Locale::fr_FR), Some(2));
+        assert_eq!(parse_month_in("FÉVR.", Locale::fr_FR), Some(2));
+        assert_eq!(parse_month_in("aout", Locale::fr_FR), Some(8));
+        assert_eq!(parse_month_in("Marz", Locale::de_DE), Some(3));
+        assert_eq!(parse_month_in("сентябрь", Locale::en_US), Some(9));
+        assert_eq!(parse_month_in("ёлка", Locale::ru_RU), None);
+    }
+
     #[test]
     fn garbage_input() {
         assert_eq!(parse_month("abc"), None);
@@ -649,7 +842,7 @@ mod display_date {
     #[test]
     fn no_arguments_returns_today() {
         let args = Args::parse_from(["cal"]);
-        let (year, month, day) = get_display_date(&args).unwrap();
+        let (year, month, day) = get_display_date(&args, &context_for(&args)).unwrap();
         let today = chrono::Local::now().date_naive();
         assert_eq!(year, today.year());
         assert_eq!(month, today.month());
@@ -659,7 +852,7 @@ mod display_date {
     #[test]
     fn single_arg_four_digit_year() {
         let args = Args::parse_from(["cal", "2026"]);
-        let (year, _month, day) = get_display_date(&args).unwrap();
+        let (year, _month, day) = get_display_date(&args, &context_for(&args)).unwrap();
         assert_eq!(year, 2026);
         assert_eq!(day, None);
     }
@@ -667,21 +860,21 @@ mod display_date {
     #[test]
     fn single_arg_month_number() {
         let args = Args::parse_from(["cal", "2"]);
-        let (_year, month, _day) = get_display_date(&args).unwrap();
+        let (_year, month, _day) = get_display_date(&args, &context_for(&args)).unwrap();
         assert_eq!(month, 2);
     }
 
     #[test]
     fn single_arg_month_name() {
         let args = Args::parse_from(["cal", "march"]);
-        let (_year, month, _day) = get_display_date(&args).unwrap();
+        let (_year, month, _day) = get_display_date(&args, &context_for(&args)).unwrap();
         assert_eq!(month, 3);
     }
 
     #[test]
     fn two_args_month_year() {
         let args = Args::parse_from(["cal", "2", "2026"]);
-        let (year, month, day) = get_display_date(&args).unwrap();
+        let (year, month, day) = get_display_date(&args, &context_for(&args)).unwrap();
         assert_eq!(year, 2026);
         assert_eq!(month, 2);
         assert_eq!(day, None);
@@ -690,7 +883,7 @@ mod display_date {
     #[test]
     fn two_args_month_name_year() {
         let args = Args::parse_from(["cal", "february", "2026"]);
-        let (year, month, _day) = get_display_date(&args).unwrap();
+        let (year, month, _day) = get_display_date(&args, &context_for(&args)).unwrap();
         assert_eq!(year, 2026);
         assert_eq!(month, 2);
     }
@@ -698,7 +891,7 @@ mod display_date {
     #[test]
     fn three_args_day_month_year() {
         let args = Args::parse_from(["cal", "15", "3", "2026"]);
-        let (year, month, day) = get_display_date(&args).unwrap();
+        let (year, month, day) = get_display_date(&args, &context_for(&args)).unwrap();
         assert_eq!(year, 2026);
         assert_eq!(month, 3);
         assert_eq!(day, Some(15));
@@ -707,31 +900,335 @@ mod display_date {
     #[test]
     fn invalid_single_arg() {
         let args = Args::parse_from(["cal", "xyz"]);
-        assert!(get_display_date(&args).is_err());
+        assert!(get_display_date(&args, &context_for(&args)).is_err());
     }
 
     #[test]
     fn invalid_month_in_two_args() {
         let args = Args::parse_from(["cal", "13", "2026"]);
-        assert!(get_display_date(&args).is_err());
+        assert!(get_display_date(&args, &context_for(&args)).is_err());
     }
 
     #[test]
     fn invalid_year_range() {
         let args = Args::parse_from(["cal", "1", "0"]);
-        assert!(get_display_date(&args).is_err());
+        assert!(get_display_date(&args, &context_for(&args)).is_err());
 
         let args = Args::parse_from(["cal", "1", "10000"]);
-        assert!(get_display_date(&args).is_err());
+        assert!(get_display_date(&args, &context_for(&args)).is_err());
     }
 
     #[test]
     fn invalid_day_range() {
         let args = Args::parse_from(["cal", "0", "1", "2026"]);
-        assert!(get_display_date(&args).is_err());
+        assert!(get_display_date(&args, &context_for(&args)).is_err());
 
         let args = Args::parse_from(["cal", "32", "1", "2026"]);
-        assert!(get_display_date(&args).is_err());
+        assert!(get_display_date(&args, &context_for(&args)).is_err());
+    }
+
+    #[test]
+    fn single_year_flags_whole_year() {
+        let args = Args::parse_from(["cal", "2026"]);
+        let date = parse_display_date(&args, &context_for(&args)).unwrap();
+        assert!(date.whole_year);
+        // Outside the util-linux layout the current month of the year is shown
+        assert_eq!(date.month, chrono::Local::now().month());
+
+        let args = Args::parse_from(["cal", "2", "2026"]);
+        assert!(
+            !parse_display_date(&args, &context_for(&args))
+                .unwrap()
+                .whole_year
+        );
+    }
+
+    #[test]
+    fn iso_dates() {
+        let args = Args::parse_from(["cal", "2026-03"]);
+        assert_eq!(
+            get_display_date(&args, &context_for(&args)).unwrap(),
+            (2026, 3, None)
+        );
+
+        let args = Args::parse_from(["cal", "2026-03-15"]);
+        assert_eq!(
+            get_display_date(&args, &context_for(&args)).unwrap(),
+            (2026, 3, Some(15))
+        );
+
+        let args = Args::parse_from(["cal", "2026-13"]);
+        assert!(get_display_date(&args, &context_for(&args)).is_err());
+    }
+
+    #[test]
+    fn dotted_and_slashed_dates() {
+        let args = Args::parse_from(["cal", "15.03.2026"]);
+        assert_eq!(
+            get_display_date(&args, &context_for(&args)).unwrap(),
+            (2026, 3, Some(15))
+        );
+
+        let args = Args::parse_from(["cal", "03.2026"]);
+        assert_eq!(
+            get_display_date(&args, &context_for(&args)).unwrap(),
+            (2026, 3, None)
+        );
+
+        // Same day and month reads the same in any locale order
+        let args = Args::parse_from(["cal", "03/03/2026"]);
+        assert_eq!(
+            get_display_date(&args, &context_for(&args)).unwrap(),
+            (2026, 3, Some(3))
+        );
+
+        let args = Args::parse_from(["cal", "32.03.2026"]);
+        assert!(get_display_date(&args, &context_for(&args)).is_err());
+    }
+
+    #[test]
+    fn relative_months() {
+        let today = chrono::Local::now().date_naive();
+        let next = today.checked_add_months(chrono::Months::new(1)).unwrap();
+        let args = Args::parse_from(["cal", "next"]);
+        assert_eq!(
+            get_display_date(&args, &context_for(&args)).unwrap(),
+            (next.year(), next.month(), None)
+        );
+
+        let args = Args::parse_from(["cal", "+1", "month"]);
+        assert_eq!(
+            get_display_date(&args, &context_for(&args)).unwrap(),
+            (next.year(), next.month(), None)
+        );
+
+        let prev = today.checked_sub_months(chrono::Months::new(13)).unwrap();
+        let args = Args::parse_from(["cal", "--", "-13"]);
+        assert_eq!(
+            get_display_date(&args, &context_for(&args)).unwrap(),
+            (prev.year(), prev.month(), None)
+        );
+    }
+
+    #[test]
+    fn relative_years() {
+        let today = chrono::Local::now().date_naive();
+        let args = Args::parse_from(["cal", "last", "year"]);
+        let date = parse_display_date(&args, &context_for(&args)).unwrap();
+        assert_eq!(date.year, today.year() - 1);
+        assert!(date.whole_year);
+
+        let args = Args::parse_from(["cal", "next", "week"]);
+        assert!(parse_display_date(&args, &context_for(&args)).is_err());
+    }
+
+    #[test]
+    fn signed_month_with_year_is_positional() {
+        let args = Args::parse_from(["cal", "+2", "2026"]);
+        assert_eq!(
+            get_display_date(&args, &context_for(&args)).unwrap(),
+            (2026, 2, None)
+        );
+    }
+
+    #[test]
+    fn month_range() {
+        let args = Args::parse_from(["cal", "2026-03..2026-08"]);
+        let date = parse_display_date(&args, &context_for(&args)).unwrap();
+        assert_eq!((date.year, date.month, date.months), (2026, 3, Some(6)));
+
+        let args = Args::parse_from(["cal", "2026-11..2027-02"]);
+        let date = parse_display_date(&args, &context_for(&args)).unwrap();
+        assert_eq!((date.year, date.month, date.months), (2026, 11, Some(4)));
+    }
+
+    #[test]
+    fn year_range_covers_whole_years() {
+        let args = Args::parse_from(["cal", "2025..2027"]);
+        let date = parse_display_date(&args, &context_for(&args)).unwrap();
+        assert_eq!((date.year, date.month, date.months), (2025, 1, Some(36)));
+
+        let args = Args::parse_from(["cal", "2025..2026-03"]);
+        assert_eq!(
+            parse_display_date(&args, &context_for(&args))
+                .unwrap()
+                .months,
+            Some(15)
+        );
+    }
+
+    #[test]
+    fn quarter_and_half() {
+        let args = Args::parse_from(["cal", "Q3", "2026"]);
+        let date = parse_display_date(&args, &context_for(&args)).unwrap();
+        assert_eq!(date.period, Some(Period::Quarter(3)));
+        assert_eq!((date.year, date.month, date.months), (2026, 7, Some(3)));
+
+        let args = Args::parse_from(["cal", "h2"]);
+        let date = parse_display_date(&args, &context_for(&args)).unwrap();
+        assert_eq!(date.period, Some(Period::Half(2)));
+        assert_eq!((date.month, date.months), (7, Some(6)));
+
+        let args = Args::parse_from(["cal", "Q5", "2026"]);
+        assert!(parse_display_date(&args, &context_for(&args)).is_err());
+    }
+
+    #[test]
+    fn period_titles() {
+        assert_eq!(Period::Quarter(3).title(2026), "Q3 2026");
+        assert_eq!(Period::Half(1).title(2026), "H1 2026");
+        assert_eq!(Period::quarter_of(2), Period::Quarter(1));
+        assert_eq!(Period::quarter_of(12), Period::Quarter(4));
+    }
+
+    #[test]
+    fn invalid_ranges() {
+        for range in [
+            "2026-08..2026-03",
+            "2027..2026",
+            "2026..0",
+            "2026..15.03.2026",
+        ] {
+            let args = Args::parse_from(["cal", range]);
+            assert!(
+                parse_display_date(&args, &context_for(&args)).is_err(),
+                "{}",
+                range
+            );
+        }
+    }
+}
+
+// ===========================================================================
+// Diagnostics for invalid arguments
+// ===========================================================================
+
+mod diagnostics {
+    use super::*;
+    use cal::args::lone_number_note;
+    use cal::error::{ErrorKind, closest, edit_distance};
+    use cal::formatter::suggest_month_in;
+
+    fn error(argv: &[&str]) -> cal::error::CalError {
+        let args = Args::parse_from(["cal"].iter().chain(argv));
+        parse_display_date(&args, &context_for(&args)).unwrap_err()
+    }
+
+    #[test]
+    fn edit_distance_counts_transpositions_once() {
+        assert_eq!(edit_distance("september", "september"), 0);
+        assert_eq!(edit_distance("setpember", "september"), 1);
+        assert_eq!(edit_distance("febuary", "february"), 1);
+        assert_eq!(edit_distance("", "may"), 3);
+        assert_eq!(closest("nxt", ["next", "last", "this"]), Some("next"));
+        assert_eq!(closest("foo", ["next", "last", "this"]), None);
+    }
+
+    #[test]
+    fn suggests_month_names() {
+        assert_eq!(
+            suggest_month_in("setpember", Locale::en_US).as_deref(),
+            Some("september")
+        );
+        assert_eq!(
+            suggest_month_in("Agust", Locale::en_US).as_deref(),
+            Some("august")
+        );
+        assert_eq!(
+            suggest_month_in("сентябрб", Locale::en_US).as_deref(),
+            Some("сентябрь")
+        );
+        assert_eq!(suggest_month_in("xyz", Locale::en_US), None);
+    }
+
+    #[test]
+    fn misspelt_month_has_a_hint() {
+        let err = error(&["setpember"]);
+        assert_eq!(err.kind, ErrorKind::Month);
+        assert_eq!(err.hint.as_deref(), Some("did you mean september?"));
+
+        let err = error(&["setpember", "2026"]);
+        assert_eq!(err.kind, ErrorKind::Month);
+        assert!(err.hint.is_some());
+
+        let err = error(&["nxt"]);
+        assert_eq!(err.kind, ErrorKind::Argument);
+        assert_eq!(err.hint.as_deref(), Some("did you mean next?"));
+    }
+
+    #[test]
+    fn impossible_dates_are_rejected() {
+        let err = error(&["31", "2", "2026"]);
+        assert_eq!(err.kind, ErrorKind::Day);
+        assert!(err.message.contains("28 days"), "{}", err);
+
+        assert_eq!(error(&["31", "4", "2026"]).kind, ErrorKind::Day);
+        assert_eq!(error(&["2026-02-30"]).kind, ErrorKind::Day);
+        assert_eq!(error(&["29", "2", "2026"]).kind, ErrorKind::Day);
+
+        // Julian leap year before the reform, Gregorian common year with --iso
+        let args = Args::parse_from(["cal", "29", "2", "1700"]);
+        assert!(parse_display_date(&args, &context_for(&args)).is_ok());
+        let args = Args::parse_from(["cal", "--iso", "29", "2", "1700"]);
+        assert!(parse_display_date(&args, &context_for(&args)).is_err());
+    }
+
+    #[test]
+    fn numbers_between_month_and_year_are_ambiguous() {
+        let err = error(&["13"]);
+        assert_eq!(err.kind, ErrorKind::Ambiguous);
+        assert!(err.hint.unwrap().contains("cal 0013"));
+
+        let args = Args::parse_from(["cal", "0013"]);
+        let date = parse_display_date(&args, &context_for(&args)).unwrap();
+        assert!(date.whole_year);
+        assert_eq!(date.year, 13);
+
+        let err = error(&["2026", "3"]);
+        assert_eq!(err.kind, ErrorKind::Ambiguous);
+        assert!(err.hint.unwrap().contains("cal 3 2026"));
+    }
+
+    #[test]
+    fn error_classes_have_distinct_exit_codes() {
+        assert_eq!(error(&["0"]).kind, ErrorKind::Month);
+        assert_eq!(error(&["1", "10000"]).kind, ErrorKind::Year);
+        assert_eq!(error(&["1", "1", "10000"]).kind, ErrorKind::Year);
+        assert_eq!(error(&["12345"]).kind, ErrorKind::Year);
+        assert_eq!(error(&["32", "1", "2026"]).kind, ErrorKind::Day);
+
+        let kinds = [
+            ErrorKind::Other,
+            ErrorKind::Argument,
+            ErrorKind::Month,
+            ErrorKind::Day,
+            ErrorKind::Year,
+            ErrorKind::Ambiguous,
+            ErrorKind::Config,
+        ];
+        let mut codes: Vec<i32> = kinds.iter().map(|kind| kind.exit_code()).collect();
+        assert_eq!(codes[0], 1);
+        // clap exits with 2 on usage errors
+        assert!(!codes.contains(&2));
+        codes.sort();
+        codes.dedup();
+        assert_eq!(codes.len(), kinds.len());
+    }
+
+    #[test]
+    fn lone_month_number_is_explained() {
+        let note = |args: &[&str]| lone_number_note(&Args::parse_from(args));
+        assert_eq!(
+            note(&["cal", "012"]).as_deref(),
+            Some("012 is taken as a month; use `cal 0012` for the year 12")
+        );
+        // Plain months and years need no note
+        assert!(note(&["cal", "12"]).is_none());
+        assert!(note(&["cal", "3"]).is_none());
+        assert!(note(&["cal", "0012"]).is_none());
+        assert!(note(&["cal", "+2"]).is_none());
+        assert!(note(&["cal", "12", "2026"]).is_none());
+        assert!(note(&["cal", "13"]).is_none());
     }
 }
 
@@ -921,3 +1418,2043 @@ mod month_grid {
         assert_eq!(next.month, 2);
     }
 }
+
+// ===========================================================================
+// Julian Day Number
+// ===========================================================================
+
+mod julian_day_number {
+    use super::*;
+
+    #[test]
+    fn known_gregorian_values() {
+        let ctx = gregorian_context();
+        assert_eq!(ctx.julian_day_number(2000, 1, 1), 2_451_545);
+        assert_eq!(ctx.julian_day_number(1970, 1, 1), 2_440_588);
+    }
+
+    #[test]
+    fn julian_calendar_offset() {
+        let ctx = julian_context();
+        // Julian 1 January 2000 is Gregorian 14 January 2000
+        assert_eq!(ctx.julian_day_number(2000, 1, 1), 2_451_558);
+    }
+
+    #[test]
+    fn round_trip_across_reform() {
+        let ctx = base_context();
+        for (y, m, d) in [
+            (1, 1, 1),
+            (1600, 3, 10),
+            (1751, 6, 1),
+            (1752, 1, 1),
+            (1752, 9, 2),
+            (1752, 9, 14),
+            (2026, 2, 18),
+        ] {
+            let jdn = ctx.julian_day_number(y, m, d);
+            assert_eq!(ctx.date_from_julian_day_number(jdn), (y, m, d));
+        }
+    }
+
+    #[test]
+    fn reform_gap_days_are_consecutive() {
+        let ctx = base_context();
+        assert_eq!(
+            ctx.julian_day_number(1752, 9, 2) + 1,
+            ctx.julian_day_number(1752, 9, 14)
+        );
+        assert_eq!(ctx.weekday(1752, 1, 1), ctx.first_day_of_month(1752, 1));
+        assert_eq!(ctx.weekday(1752, 9, 2), Weekday::Wed);
+    }
+
+    #[test]
+    fn from_gregorian_before_reform() {
+        let ctx = base_context();
+        let date = chrono::NaiveDate::from_ymd_opt(1600, 3, 20).unwrap();
+        assert_eq!(ctx.from_gregorian(date), (1600, 3, 10));
+    }
+}
+
+// ===========================================================================
+// Equinoxes and solstices
+// ===========================================================================
+
+mod seasons {
+    use super::*;
+    use cal::astro::{Season, season_instant};
+    use cal::formatter::{footer_notes, get_system_locale, print_months_side_by_side};
+
+    fn assert_close(year: i32, season: Season, expected: &str) {
+        let expected = chrono::NaiveDateTime::parse_from_str(expected, "%Y-%m-%d %H:%M")
+            .unwrap()
+            .and_utc();
+        let actual = season_instant(year, season);
+        let diff = (actual - expected).num_seconds().abs();
+        assert!(diff < 180, "{season:?} {year}: {actual} vs {expected}");
+    }
+
+    #[test]
+    fn instants_2026() {
+        assert_close(2026, Season::MarchEquinox, "2026-03-20 14:46");
+        assert_close(2026, Season::JuneSolstice, "2026-06-21 08:24");
+        assert_close(2026, Season::SeptemberEquinox, "2026-09-23 00:05");
+        assert_close(2026, Season::DecemberSolstice, "2026-12-21 20:50");
+    }
+
+    #[test]
+    fn instants_2000() {
+        assert_close(2000, Season::MarchEquinox, "2000-03-20 07:35");
+        assert_close(2000, Season::DecemberSolstice, "2000-12-21 13:37");
+    }
+
+    #[test]
+    fn season_months() {
+        for season in Season::ALL {
+            assert_eq!(Season::for_month(season.month()), Some(season));
+        }
+        assert_eq!(Season::for_month(1), None);
+    }
+
+    #[test]
+    fn marked_in_grid() {
+        let mut ctx = base_context();
+        ctx.color = true;
+        ctx.seasons = true;
+        let m = MonthData::new(&ctx, 2026, 3);
+        let body = format_month_grid(&ctx, &m)[2..].join("\n");
+        assert!(body.contains("\x1b[95m20") || body.contains("\x1b[95m21"));
+
+        ctx.seasons = false;
+        let body = format_month_grid(&ctx, &m)[2..].join("\n");
+        assert!(!body.contains("\x1b[95m"));
+    }
+
+    #[test]
+    fn starred_without_color() {
+        let mut ctx = base_context();
+        ctx.seasons = true;
+        let grid = format_month_grid(&ctx, &MonthData::new(&ctx, 2026, 3));
+        assert!(grid.iter().any(|line| line.contains(" 20*21 ")));
+        // The June solstice falls on a Sunday: the mark ends the row
+        let grid = format_month_grid(&ctx, &MonthData::new(&ctx, 2026, 6));
+        assert!(grid.iter().any(|line| line.ends_with(" 21*")));
+    }
+
+    #[test]
+    fn mark_after_last_column_takes_the_gutter() {
+        let mut ctx = base_context();
+        ctx.seasons = true;
+        let months: Vec<MonthData> = (5..=7).map(|m| MonthData::new(&ctx, 2026, m)).collect();
+        let mut out = Vec::new();
+        print_months_side_by_side(&mut out, &ctx, &months).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let row = text.lines().find(|line| line.contains("21*")).unwrap();
+        assert_eq!(row.width(), 20 * 3 + 2 * 2);
+        assert!(row.contains("21* 13"));
+    }
+
+    #[test]
+    fn names_follow_locale() {
+        assert_eq!(Season::MarchEquinox.name_in(Locale::en_US), "March equinox");
+        assert_eq!(
+            Season::DecemberSolstice.name_in(Locale::ru_RU),
+            "декабрьское солнцестояние"
+        );
+    }
+
+    #[test]
+    fn footer_lists_displayed_seasons() {
+        let mut ctx = base_context();
+        ctx.seasons = true;
+        let months: Vec<MonthData> = (1..=12).map(|m| MonthData::new(&ctx, 2026, m)).collect();
+        let notes = footer_notes(&ctx, &months);
+        assert_eq!(notes.len(), 4);
+        let locale = get_system_locale();
+        assert!(notes[0].starts_with(Season::MarchEquinox.name_in(locale)));
+        assert!(notes[3].starts_with(Season::DecemberSolstice.name_in(locale)));
+
+        ctx.seasons = false;
+        assert!(footer_notes(&ctx, &months).is_empty());
+    }
+}
+
+// ===========================================================================
+// Sunrise and sunset
+// ===========================================================================
+
+mod sunrise_sunset {
+    use super::*;
+    use cal::astro::{Daylight, Location, sun_times};
+    use cal::formatter::footer_notes;
+
+    #[test]
+    fn parse_coordinates() {
+        let loc = Location::parse("55.75,37.62").unwrap();
+        assert_eq!(loc.latitude, 55.75);
+        assert_eq!(loc.longitude, 37.62);
+        assert_eq!(loc.name, "55.75°N 37.62°E");
+
+        let loc = Location::parse("-33.87, -70.65").unwrap();
+        assert_eq!(loc.name, "33.87°S 70.65°W");
+    }
+
+    #[test]
+    fn parse_city_names() {
+        assert_eq!(Location::parse("moscow").unwrap().name, "Moscow");
+        assert_eq!(Location::parse("new-york").unwrap().name, "New York");
+        assert_eq!(
+            Location::parse("SAINT_PETERSBURG").unwrap().name,
+            "Saint Petersburg"
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(Location::parse("91,0").is_err());
+        assert!(Location::parse("0,181").is_err());
+        assert!(Location::parse("atlantis").is_err());
+    }
+
+    #[test]
+    fn moscow_summer_solstice() {
+        let ctx = base_context();
+        let loc = Location::parse("moscow").unwrap();
+        let daylight = sun_times(ctx.julian_day_number(2026, 6, 21), &loc);
+        let Daylight::Normal { sunrise, sunset } = daylight else {
+            panic!("expected sunrise and sunset");
+        };
+        // Sunrise about 00:45 UTC, sunset about 18:20 UTC
+        assert_eq!(sunrise.format("%H").to_string(), "00");
+        assert_eq!(sunset.format("%H").to_string(), "18");
+        assert!((17 * 60..18 * 60).contains(&daylight.length_minutes()));
+    }
+
+    #[test]
+    fn equator_equinox_twelve_hours() {
+        let ctx = base_context();
+        let loc = Location::parse("0,0").unwrap();
+        let minutes = sun_times(ctx.julian_day_number(2026, 3, 20), &loc).length_minutes();
+        assert!((12 * 60..12 * 60 + 10).contains(&minutes), "{minutes}");
+    }
+
+    #[test]
+    fn polar_night_and_day() {
+        let ctx = base_context();
+        let loc = Location::parse("murmansk").unwrap();
+        let winter = sun_times(ctx.julian_day_number(2026, 12, 21), &loc);
+        assert_eq!(winter, Daylight::PolarNight);
+        assert_eq!(winter.length_minutes(), 0);
+        let summer = sun_times(ctx.julian_day_number(2026, 6, 21), &loc);
+        assert_eq!(summer, Daylight::PolarDay);
+        assert!(summer.is_polar());
+    }
+
+    #[test]
+    fn agenda_lists_every_day() {
+        let mut ctx = base_context();
+        ctx.location = Some(Location::parse("moscow").unwrap());
+        let months = [MonthData::new(&ctx, 2026, 2)];
+        let notes = footer_notes(&ctx, &months);
+        assert_eq!(notes.len(), 1 + 28);
+        assert!(notes[0].contains("Moscow"));
+        assert!(notes[1].starts_with("2026-02-01"));
+    }
+
+    #[test]
+    fn polar_days_marked_in_grid() {
+        let mut ctx = base_context();
+        ctx.color = true;
+        ctx.location = Some(Location::parse("norilsk").unwrap());
+        ctx.polar = true;
+        let m = MonthData::new(&ctx, 2026, 12);
+        let body = format_month_grid(&ctx, &m)[2..].join("\n");
+        assert!(body.contains("\x1b[95m21"));
+
+        ctx.color = false;
+        let body = format_month_grid(&ctx, &m)[2..].join("\n");
+        assert!(body.contains("21*"));
+    }
+
+    #[test]
+    fn polar_names_follow_locale() {
+        assert_eq!(
+            Daylight::PolarNight.polar_name_in(Locale::ru_RU),
+            Some("полярная ночь")
+        );
+        assert_eq!(
+            Daylight::PolarDay.polar_name_in(Locale::en_US),
+            Some("polar day")
+        );
+    }
+
+    #[test]
+    fn polar_requires_location() {
+        assert!(Args::try_parse_from(["cal", "--polar"]).is_err());
+        let args = Args::parse_from(["cal", "--location", "tromso", "--polar"]);
+        let ctx = CalContext::new(&args).unwrap();
+        assert!(ctx.polar);
+        assert_eq!(ctx.location.unwrap().name, "Tromso");
+    }
+}
+
+// ===========================================================================
+// Time zones and offset changes
+// ===========================================================================
+
+mod zoneinfo {
+    use super::*;
+    use cal::formatter::footer_notes;
+    use cal::zoneinfo::TimeZone;
+
+    fn ts(s: &str) -> i64 {
+        chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M")
+            .unwrap()
+            .and_utc()
+            .timestamp()
+    }
+
+    /// Build a version 2 TZif file with one transition and a POSIX footer.
+    fn tzif_v2(transition: i64, footer: &str) -> Vec<u8> {
+        fn header(timecnt: u32, typecnt: u32, charcnt: u32) -> Vec<u8> {
+            let mut h = b"TZif2".to_vec();
+            h.extend([0u8; 15]);
+            for count in [0, 0, 0, timecnt, typecnt, charcnt] {
+                h.extend(u32::to_be_bytes(count));
+            }
+            h
+        }
+        let chars = b"LMT\0STD\0";
+        // Empty v1 block: a single type
+        let mut data = header(0, 1, 4);
+        data.extend(0i32.to_be_bytes());
+        data.extend([0, 0]);
+        data.extend(b"UTC\0");
+        // v2 block: LMT (+00:30) until the transition, then STD (+01:00)
+        data.extend(header(1, 2, chars.len() as u32));
+        data.extend(transition.to_be_bytes());
+        data.push(1);
+        data.extend(1800i32.to_be_bytes());
+        data.extend([0, 0]);
+        data.extend(3600i32.to_be_bytes());
+        data.extend([0, 4]);
+        data.extend(chars);
+        data.extend(format!("\n{}\n", footer).bytes());
+        data
+    }
+
+    #[test]
+    fn posix_rule_europe() {
+        let zone = TimeZone::load("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+        let changes = zone.offset_changes(ts("2026-01-01 00:00"), ts("2027-01-01 00:00"));
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].at, ts("2026-03-29 01:00"));
+        assert_eq!(changes[0].before.abbreviation, "CET");
+        assert_eq!(changes[0].after.abbreviation, "CEST");
+        assert_eq!(changes[0].after.offset_string(), "+02:00");
+        assert_eq!(changes[1].at, ts("2026-10-25 01:00"));
+        assert_eq!(changes[1].local_time().format("%H:%M").to_string(), "03:00");
+    }
+
+    #[test]
+    fn posix_rule_us() {
+        let zone = TimeZone::load("EST5EDT,M3.2.0,M11.1.0").unwrap();
+        let changes = zone.offset_changes(ts("2026-01-01 00:00"), ts("2027-01-01 00:00"));
+        assert_eq!(changes[0].at, ts("2026-03-08 07:00"));
+        assert_eq!(changes[1].at, ts("2026-11-01 06:00"));
+        assert_eq!(changes[0].before.offset_string(), "-05:00");
+    }
+
+    #[test]
+    fn posix_rule_southern_hemisphere() {
+        let zone = TimeZone::load("AEST-10AEDT,M10.1.0,M4.1.0/3").unwrap();
+        assert!(zone.local_type_at(ts("2026-01-15 00:00")).is_dst);
+        assert!(!zone.local_type_at(ts("2026-07-15 00:00")).is_dst);
+    }
+
+    #[test]
+    fn posix_rule_without_dst() {
+        let zone = TimeZone::load("<+03>-3").unwrap();
+        assert_eq!(zone.local_type_at(0).utc_offset, 3 * 3600);
+        assert!(zone.offset_changes(0, ts("2030-01-01 00:00")).is_empty());
+    }
+
+    #[test]
+    fn unknown_zone() {
+        assert!(TimeZone::load("Nowhere/Land").is_err());
+        assert!(TimeZone::load("../etc/passwd").is_err());
+    }
+
+    #[test]
+    fn parse_tzif_v2_with_footer() {
+        let data = tzif_v2(ts("2000-01-01 00:00"), "STD-1DST,M3.5.0,M10.5.0/3");
+        let zone = TimeZone::parse("Test/Zone", &data).unwrap();
+        assert_eq!(zone.local_type_at(ts("1999-06-01 00:00")).utc_offset, 1800);
+        assert_eq!(
+            zone.local_type_at(ts("2000-02-01 00:00")).abbreviation,
+            "STD"
+        );
+        // After the last transition the footer rule applies
+        assert!(zone.local_type_at(ts("2026-07-01 00:00")).is_dst);
+
+        let changes = zone.offset_changes(ts("1999-12-01 00:00"), ts("2000-12-01 00:00"));
+        assert_eq!(changes.len(), 3);
+        assert_eq!(changes[0].before.abbreviation, "LMT");
+    }
+
+    #[test]
+    fn parse_invalid_tzif() {
+        assert!(TimeZone::parse("x", b"not a tzif file").is_err());
+        let data = tzif_v2(0, "STD-1");
+        assert!(TimeZone::parse("x", &data[..60]).is_err());
+    }
+
+    #[test]
+    fn system_zoneinfo_when_available() {
+        if let Ok(zone) = TimeZone::load("Europe/Berlin") {
+            let changes = zone.offset_changes(ts("2026-01-01 00:00"), ts("2027-01-01 00:00"));
+            assert_eq!(changes.len(), 2);
+            assert_eq!(changes[0].at, ts("2026-03-29 01:00"));
+        }
+    }
+
+    #[test]
+    fn transition_days_marked_and_listed() {
+        let mut ctx = base_context();
+        ctx.color = true;
+        ctx.dst = true;
+        ctx.time_zones = vec![TimeZone::load("CET-1CEST,M3.5.0,M10.5.0/3").unwrap()];
+        let m = MonthData::new(&ctx, 2026, 3);
+        let body = format_month_grid(&ctx, &m)[2..].join("\n");
+        assert!(body.contains("\x1b[95m29"));
+
+        let notes = footer_notes(&ctx, &[m]);
+        assert_eq!(notes.len(), 1);
+        assert!(notes[0].contains("2026-03-29 02:00"));
+        assert!(notes[0].contains("CET (+01:00) -> CEST (+02:00)"));
+    }
+
+    #[test]
+    fn tz_option_loads_zones() {
+        let args = Args::parse_from(["cal", "--tz", "EST5EDT", "--tz", "<+03>-3"]);
+        let ctx = CalContext::new(&args).unwrap();
+        assert_eq!(ctx.time_zones.len(), 2);
+
+        let args = Args::parse_from(["cal", "--dst"]);
+        let ctx = CalContext::new(&args).unwrap();
+        assert_eq!(ctx.time_zones.len(), 1);
+
+        let args = Args::parse_from(["cal", "--tz", "Nowhere/Land"]);
+        assert!(CalContext::new(&args).is_err());
+    }
+}
+
+// ===========================================================================
+// Today in several time zones
+// ===========================================================================
+
+mod zone_today {
+    use super::*;
+    use cal::clock::Clock;
+    use cal::formatter::footer_notes;
+    use cal::zoneinfo::TimeZone;
+
+    #[test]
+    fn today_in_other_zone_is_underlined() {
+        let mut ctx = base_context();
+        ctx.color = true;
+        ctx.zone_todays = vec![(
+            "Australia/Sydney".to_string(),
+            chrono::NaiveDate::from_ymd_opt(2026, 2, 19).unwrap(),
+        )];
+        let m = MonthData::new(&ctx, 2026, 2);
+        let body = format_month_grid(&ctx, &m)[2..].join("\n");
+        assert!(body.contains("\x1b[7m18"));
+        assert!(body.contains("\x1b[4m19"));
+    }
+
+    #[test]
+    fn today_in_zone_is_a_valid_date() {
+        let zone = TimeZone::load("<+14>-14").unwrap();
+        let west = TimeZone::load("<-12>12").unwrap();
+        let diff = Clock::System.today(Some(&zone)) - Clock::System.today(Some(&west));
+        // +14 is always one or two days ahead
+        assert!((1..=2).contains(&diff.num_days()));
+    }
+
+    #[test]
+    fn zone_todays_only_where_date_differs() {
+        let args = Args::parse_from(["cal", "--tz", "<+14>-14", "--tz", "<+13>-13"]);
+        let ctx = CalContext::new(&args).unwrap();
+        assert!(ctx.zone_todays.iter().all(|(_, date)| *date != ctx.today));
+    }
+
+    #[test]
+    fn unknown_zone_is_reported_for_today() {
+        let args = Args::parse_from(["cal", "--tz", "Nowhere/Land", "3", "2026"]);
+        assert!(CalContext::new(&args).is_err());
+    }
+
+    #[test]
+    fn footer_lists_clock_per_zone() {
+        let mut ctx = base_context();
+        ctx.time_zones = vec![
+            TimeZone::load("EST5EDT").unwrap(),
+            TimeZone::load("<+03>-3").unwrap(),
+        ];
+        let notes = footer_notes(&ctx, &[MonthData::new(&ctx, 2026, 2)]);
+        assert_eq!(notes.len(), 2);
+        assert!(notes[0].starts_with("EST5EDT"));
+        assert!(notes[1].ends_with("+03"));
+
+        ctx.time_zones.truncate(1);
+        assert!(footer_notes(&ctx, &[MonthData::new(&ctx, 2026, 2)]).is_empty());
+    }
+}
+
+// ===========================================================================
+// Search queries
+// ===========================================================================
+
+mod find {
+    use super::*;
+    use cal::args::parse_year_range;
+    use cal::formatter::footer_notes;
+    use cal::query::{Match, Nth, Query};
+
+    #[test]
+    fn parse_query_forms() {
+        assert_eq!(
+            Query::parse("Friday the 13th").unwrap(),
+            Query::WeekdayOnDay {
+                weekday: Weekday::Fri,
+                day: 13
+            }
+        );
+        assert_eq!(
+            Query::parse("last mon of may").unwrap(),
+            Query::NthWeekday {
+                nth: Nth::Last,
+                weekday: Weekday::Mon,
+                month: Some(5)
+            }
+        );
+        assert_eq!(
+            Query::parse("29 feb").unwrap(),
+            Query::DayOfMonth { day: 29, month: 2 }
+        );
+        assert_eq!(
+            Query::parse("months starting on sundays").unwrap(),
+            Query::MonthsStarting(Weekday::Sun)
+        );
+        assert!(Query::parse("blah").is_err());
+        assert!(Query::parse("fri 32").is_err());
+    }
+
+    #[test]
+    fn friday_13th_in_2026() {
+        let ctx = base_context();
+        let query = Query::parse("fri 13").unwrap();
+        assert_eq!(
+            query.find(&ctx, 2026, 2026),
+            vec![
+                Match::Day(2026, 2, 13),
+                Match::Day(2026, 3, 13),
+                Match::Day(2026, 11, 13)
+            ]
+        );
+    }
+
+    #[test]
+    fn nth_weekday() {
+        let ctx = base_context();
+        let memorial = Query::parse("last monday of may").unwrap();
+        assert_eq!(
+            memorial.find(&ctx, 2026, 2026),
+            vec![Match::Day(2026, 5, 25)]
+        );
+        let thanksgiving = Query::parse("4th thu of nov").unwrap();
+        assert_eq!(
+            thanksgiving.find(&ctx, 2026, 2026),
+            vec![Match::Day(2026, 11, 26)]
+        );
+        // Fifth weekday exists only in some months
+        let fifth = Query::parse("5th fri").unwrap();
+        assert!(fifth.find(&ctx, 2026, 2026).len() < 12);
+    }
+
+    #[test]
+    fn months_starting_on_weekday() {
+        let ctx = base_context();
+        let query = Query::parse("months starting on sunday").unwrap();
+        assert_eq!(
+            query.find(&ctx, 2026, 2026),
+            vec![
+                Match::Month(2026, 2),
+                Match::Month(2026, 3),
+                Match::Month(2026, 11)
+            ]
+        );
+    }
+
+    #[test]
+    fn leap_day_follows_reform() {
+        let mut ctx = base_context();
+        let query = Query::parse("29 feb").unwrap();
+        assert_eq!(query.find(&ctx, 1700, 1700), vec![Match::Day(1700, 2, 29)]);
+        ctx.reform_year = ReformType::Gregorian.reform_year();
+        assert!(query.find(&ctx, 1700, 1700).is_empty());
+    }
+
+    #[test]
+    fn reform_year_matches_the_grid() {
+        let ctx = base_context();
+        let query = Query::parse("fri 13").unwrap();
+        assert_eq!(
+            query.find(&ctx, 1752, 1752),
+            vec![Match::Day(1752, 3, 13), Match::Day(1752, 10, 13)]
+        );
+        let query = Query::parse("wed 2").unwrap();
+        assert_eq!(query.find(&ctx, 1752, 1752), vec![Match::Day(1752, 9, 2)]);
+    }
+
+    #[test]
+    fn reform_gap_days_never_match() {
+        let ctx = base_context();
+        let query = Query::parse("10 sep").unwrap();
+        assert!(query.find(&ctx, 1752, 1752).is_empty());
+    }
+
+    #[test]
+    fn footer_lists_matches() {
+        let mut ctx = base_context();
+        ctx.find = Some(Query::parse("fri 13").unwrap());
+        let notes = footer_notes(&ctx, &[MonthData::new(&ctx, 2026, 2)]);
+        assert_eq!(notes[0], "Matches for Fri 13:");
+        assert!(notes[1].starts_with("2026-02-13 "));
+
+        let notes = footer_notes(&ctx, &[MonthData::new(&ctx, 2026, 4)]);
+        assert_eq!(notes, vec!["Matches for Fri 13: none"]);
+    }
+
+    #[test]
+    fn matches_are_highlighted() {
+        let mut ctx = base_context();
+        ctx.color = true;
+        ctx.find = Some(Query::parse("fri 13").unwrap());
+        let m = MonthData::new(&ctx, 2026, 3);
+        let body = format_month_grid(&ctx, &m)[2..].join("\n");
+        assert!(body.contains("\x1b[95m13"));
+
+        // Without color the match is starred
+        ctx.color = false;
+        let body = format_month_grid(&ctx, &m)[2..].join("\n");
+        assert!(body.contains(" 13*14 "));
+        assert_eq!(body.matches('*').count(), 1);
+    }
+
+    #[test]
+    fn year_range() {
+        assert_eq!(parse_year_range("2026..2035"), Ok(Some((2026, 2035))));
+        assert_eq!(parse_year_range("2026"), Ok(None));
+        let err = parse_year_range("2035..2026").unwrap_err();
+        assert_eq!(err.kind, cal::error::ErrorKind::Argument);
+        let err = parse_year_range("0..10").unwrap_err();
+        assert_eq!(err.kind, cal::error::ErrorKind::Year);
+        assert_eq!(err.message, "Invalid year in range: 0 (must be 1-9999)");
+    }
+}
+
+// ===========================================================================
+// Years with the same layout
+// ===========================================================================
+
+mod same_layout {
+    use super::*;
+    use cal::types::LayoutMatch;
+
+    #[test]
+    fn full_matches_for_2026() {
+        let ctx = base_context();
+        let years: Vec<i32> = ctx
+            .same_layout_years(2026, 2000, 2050, false)
+            .into_iter()
+            .map(|(year, _)| year)
+            .collect();
+        assert_eq!(years, vec![2009, 2015, 2037, 2043]);
+    }
+
+    #[test]
+    fn partial_matches() {
+        let ctx = base_context();
+        // 2032 is a leap year, so only January lines up
+        assert_eq!(ctx.layout_match(2026, 2032), Some(LayoutMatch::January));
+        assert_eq!(ctx.layout_match(2032, 2004), Some(LayoutMatch::Full));
+        assert_eq!(ctx.layout_match(2026, 2020), Some(LayoutMatch::MarDec));
+        assert_eq!(ctx.layout_match(2026, 2027), None);
+        assert!(
+            ctx.same_layout_years(2026, 2000, 2050, true)
+                .contains(&(2048, LayoutMatch::MarDec))
+        );
+    }
+
+    #[test]
+    fn reform_year_is_unique() {
+        let ctx = base_context();
+        assert!(
+            ctx.same_layout_years(1752, 1600, 1900, true)
+                .iter()
+                .all(|&(_, kind)| matches!(kind, LayoutMatch::JanFeb | LayoutMatch::January))
+        );
+    }
+
+    #[test]
+    fn follows_active_reform() {
+        let mut ctx = base_context();
+        // 1700 is a leap year only in the Julian calendar
+        assert_ne!(ctx.layout_match(1700, 2000), Some(LayoutMatch::Full));
+        ctx.reform_year = ReformType::Gregorian.reform_year();
+        assert_eq!(ctx.layout_match(1700, 1706), Some(LayoutMatch::Full));
+    }
+}
+
+// ===========================================================================
+// Date info report
+// ===========================================================================
+
+mod info {
+    use super::*;
+    use cal::info::{InfoScope, info_report};
+
+    fn value(rows: &[(&str, String)], label: &str) -> String {
+        rows.iter()
+            .find(|(l, _)| *l == label)
+            .map(|(_, v)| v.clone())
+            .unwrap()
+    }
+
+    #[test]
+    fn easter_dates() {
+        let mut ctx = base_context();
+        assert_eq!(ctx.easter(2026), (4, 5));
+        assert_eq!(ctx.easter(2024), (3, 31));
+        assert_eq!(ctx.easter(2000), (4, 23));
+        // Julian computus before the reform
+        assert_eq!(ctx.easter(1700), (3, 31));
+        ctx.reform_year = ReformType::Julian.reform_year();
+        assert_eq!(ctx.easter(2026), (3, 30));
+    }
+
+    #[test]
+    fn days_in_year_excludes_reform_gap() {
+        let ctx = base_context();
+        assert_eq!(ctx.days_in_year(2026), 365);
+        assert_eq!(ctx.days_in_year(2024), 366);
+        assert_eq!(ctx.days_in_year(1752), 355);
+    }
+
+    #[test]
+    fn to_gregorian_round_trip() {
+        let ctx = base_context();
+        let date = ctx.to_gregorian(1700, 1, 1).unwrap();
+        assert_eq!(date, chrono::NaiveDate::from_ymd_opt(1700, 1, 11).unwrap());
+        assert_eq!(ctx.from_gregorian(date), (1700, 1, 1));
+    }
+
+    #[test]
+    fn day_report() {
+        let ctx = base_context();
+        let rows = info_report(&ctx, InfoScope::Day(2027, 1, 1));
+        assert_eq!(value(&rows, "Day of year"), "1");
+        assert_eq!(value(&rows, "Days remaining"), "364");
+        assert_eq!(value(&rows, "ISO week date"), "2026-W53-5");
+        assert_eq!(value(&rows, "Julian Day Number"), "2461407");
+        assert_eq!(value(&rows, "Quarter"), "Q1");
+    }
+
+    #[test]
+    fn day_report_in_reform_year() {
+        let ctx = base_context();
+        let weekday = |scope| value(&info_report(&ctx, scope), "Date")[11..].to_string();
+        // 1 January 1752 was a Wednesday, as was 1 January 2025
+        assert_eq!(
+            weekday(InfoScope::Day(1752, 1, 1)),
+            weekday(InfoScope::Day(2025, 1, 1))
+        );
+        assert_eq!(
+            weekday(InfoScope::Day(1752, 9, 2)),
+            weekday(InfoScope::Day(2025, 1, 1))
+        );
+        let jdn = |day| {
+            value(
+                &info_report(&ctx, InfoScope::Day(1752, 9, day)),
+                "Julian Day Number",
+            )
+        };
+        assert_eq!(jdn(2), "2361221");
+        assert_eq!(jdn(14), "2361222");
+    }
+
+    #[test]
+    fn year_report() {
+        let ctx = base_context();
+        let rows = info_report(&ctx, InfoScope::Year(2026));
+        assert_eq!(value(&rows, "Leap year"), "no");
+        assert_eq!(value(&rows, "Days"), "365");
+        assert_eq!(value(&rows, "ISO weeks"), "53");
+        assert_eq!(value(&rows, "Easter"), "2026-04-05");
+    }
+
+    #[test]
+    fn month_report_skips_reform_gap() {
+        let ctx = base_context();
+        let rows = info_report(&ctx, InfoScope::Month(1752, 9));
+        assert_eq!(value(&rows, "Days"), "19");
+        assert_eq!(value(&rows, "Quarter"), "Q3");
+    }
+
+    #[test]
+    fn scope_from_args() {
+        let ctx = base_context();
+        let scope = |argv: &[&str]| {
+            let args = Args::parse_from(["cal", "--info"].iter().chain(argv));
+            InfoScope::from_args(&ctx, &args)
+        };
+        assert_eq!(scope(&["2026"]), Ok(InfoScope::Year(2026)));
+        assert_eq!(scope(&["2", "2026"]), Ok(InfoScope::Month(2026, 2)));
+        assert_eq!(scope(&["18", "2", "2026"]), Ok(InfoScope::Day(2026, 2, 18)));
+        assert!(scope(&["31", "2", "2026"]).is_err());
+        assert!(scope(&["5", "9", "1752"]).is_err());
+        // A four-digit year and a relative year are years, not months
+        assert_eq!(scope(&["0012"]), Ok(InfoScope::Year(12)));
+        let next_year = ctx.today.year() + 1;
+        assert_eq!(scope(&["next", "year"]), Ok(InfoScope::Year(next_year)));
+        for range in ["2025..2027", "2026-03..2026-05", "Q3"] {
+            let err = scope(&[range]).unwrap_err();
+            assert_eq!(err.kind, cal::error::ErrorKind::Argument, "{range}");
+        }
+    }
+}
+
+// ===========================================================================
+// Selected day highlighting
+// ===========================================================================
+
+mod selected_day {
+    use super::*;
+
+    #[test]
+    fn day_argument_is_selected() {
+        let args = Args::parse_from(["cal", "15", "9", "2026"]);
+        let ctx = CalContext::new(&args).unwrap();
+        assert_eq!(ctx.selected, Some((2026, 9, 15)));
+
+        let args = Args::parse_from(["cal", "9", "2026"]);
+        assert_eq!(CalContext::new(&args).unwrap().selected, None);
+    }
+
+    #[test]
+    fn selected_day_is_highlighted_apart_from_today() {
+        let mut ctx = base_context();
+        ctx.color = true;
+        ctx.selected = Some((2026, 2, 5));
+        let m = MonthData::new(&ctx, 2026, 2);
+        let body = format_month_grid(&ctx, &m)[2..].join("\n");
+        assert!(body.contains("\x1b[30;103m 5"));
+        assert!(body.contains("\x1b[7m18"));
+    }
+
+    #[test]
+    fn selected_day_in_julian_layout() {
+        let mut ctx = base_context();
+        ctx.color = true;
+        ctx.julian = true;
+        ctx.selected = Some((2026, 2, 5));
+        let m = MonthData::new(&ctx, 2026, 2);
+        let body = format_month_grid(&ctx, &m)[2..].join("\n");
+        assert!(body.contains("\x1b[30;103m 36"));
+        // Today and weekends keep the plain day-of-year cells
+        assert_eq!(body.matches('\x1b').count(), 2);
+    }
+
+    #[test]
+    fn day_argument_is_parsed_once_into_the_context() {
+        let args = Args::parse_from(["cal", "15", "9", "2026"]);
+        let ctx = CalContext::new(&args).unwrap();
+        assert_eq!(ctx.date, Ok(DisplayDate::day(2026, 9, 15)));
+
+        let args = Args::parse_from(["cal", "31", "9", "2026"]);
+        let ctx = CalContext::new(&args).unwrap();
+        assert_eq!(ctx.date.unwrap_err().kind, cal::error::ErrorKind::Day);
+        assert_eq!(ctx.selected, None);
+    }
+
+    #[test]
+    fn no_highlight_without_color() {
+        let mut ctx = base_context();
+        ctx.selected = Some((2026, 2, 5));
+        let m = MonthData::new(&ctx, 2026, 2);
+        assert!(!format_month_grid(&ctx, &m).join("").contains('\x1b'));
+    }
+}
+
+// ===========================================================================
+// Single-week view
+// ===========================================================================
+
+mod week_view {
+    use super::*;
+    use cal::args::week_to_show;
+    use cal::error::ErrorKind;
+    use cal::formatter::format_week_strip;
+    use chrono::NaiveDate;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn iso_week_dates() {
+        let ctx = base_context();
+        let dates = ctx.week_dates(2026, 9).unwrap();
+        assert_eq!(dates[0], date(2026, 2, 23));
+        assert_eq!(dates[6], date(2026, 3, 1));
+        assert!(ctx.week_dates(2026, 53).is_some());
+        assert!(ctx.week_dates(2027, 53).is_none());
+        assert!(ctx.week_dates(2026, 0).is_none());
+    }
+
+    #[test]
+    fn us_week_dates() {
+        let mut ctx = base_context();
+        ctx.week_type = WeekType::Us;
+        let dates = ctx.week_dates(2027, 1).unwrap();
+        assert_eq!(dates[0], date(2026, 12, 27));
+        assert_eq!(dates[0].weekday(), Weekday::Sun);
+        assert!(ctx.week_dates(2027, 54).is_none());
+    }
+
+    #[test]
+    fn week_of_date() {
+        let mut ctx = base_context();
+        assert_eq!(ctx.week_of(date(2027, 1, 1)), (2026, 53));
+        ctx.week_type = WeekType::Us;
+        assert_eq!(ctx.week_of(date(2027, 1, 1)), (2027, 1));
+    }
+
+    #[test]
+    fn strip_straddles_months() {
+        let ctx = base_context();
+        let dates = ctx.week_dates(2026, 9).unwrap();
+        let lines = format_week_strip(&ctx, 2026, 9, &dates);
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].contains("2026"));
+        assert!(lines[0].contains('9'));
+        assert!(lines[2].starts_with("23 "));
+        assert!(lines[2].ends_with("1 Мар") || lines[2].ends_with("1 Mar"));
+        // Columns line up between names and dates
+        let second_column = |line: &str| {
+            let first_end = line.find("  ").unwrap();
+            let rest = &line[first_end..];
+            line[..first_end].width() + rest.len() - rest.trim_start().len()
+        };
+        assert_eq!(second_column(&lines[1]), second_column(&lines[2]));
+    }
+
+    #[test]
+    fn strip_highlights_today() {
+        let mut ctx = base_context();
+        ctx.color = true;
+        let dates = ctx.week_dates(2026, 8).unwrap();
+        let lines = format_week_strip(&ctx, 2026, 8, &dates);
+        assert!(lines[2].contains("\x1b[7m18 "));
+    }
+
+    #[test]
+    fn week_and_year_arguments() {
+        let week = |extra: &[&str]| {
+            let mut argv = vec!["cal", "--today", "2026-02-18", "--week-type", "iso"];
+            argv.extend(extra);
+            let args = Args::parse_from(argv);
+            week_to_show(&args, &context_for(&args))
+        };
+        assert_eq!(week(&["--week"]), Ok((2026, 8)));
+        assert_eq!(week(&["--week", "10"]), Ok((2026, 10)));
+        assert_eq!(week(&["--week", "10", "2027"]), Ok((2027, 10)));
+        // No year has a week 2030: it is the year
+        assert_eq!(week(&["--week", "2030"]), Ok((2030, 8)));
+        assert_eq!(
+            week(&["--week", "10", "20x"]).unwrap_err().kind,
+            ErrorKind::Year
+        );
+        assert_eq!(
+            week(&["--week", "12345"]).unwrap_err().kind,
+            ErrorKind::Year
+        );
+    }
+}
+
+// ===========================================================================
+// Color palette
+// ===========================================================================
+
+mod palette {
+    use super::*;
+    use cal::colors::{TerminalColors, parse_color};
+
+    #[test]
+    fn color_values() {
+        assert_eq!(parse_color("red").as_deref(), Some("\x1b[31m"));
+        assert_eq!(parse_color("1;31").as_deref(), Some("\x1b[1;31m"));
+        assert_eq!(parse_color("\\e[7m").as_deref(), Some("\x1b[7m"));
+        assert_eq!(parse_color("\\033[4m").as_deref(), Some("\x1b[4m"));
+        assert_eq!(parse_color("purple"), None);
+        assert_eq!(parse_color(""), None);
+    }
+
+    #[test]
+    fn scheme_file() {
+        let mut palette = Palette::default();
+        palette.apply_scheme("# comment\ntoday   lightred\nweekend 32  # trailing\nbogus red\n");
+        assert_eq!(palette.today, "\x1b[1;31m");
+        assert_eq!(palette.weekend, "\x1b[32m");
+        assert_eq!(palette.header, Palette::default().header);
+    }
+
+    #[test]
+    fn cal_colors_variable() {
+        let mut palette = Palette::default();
+        palette.apply_cal_colors("header=blue:holiday=1;35:workday=gray:oops");
+        assert_eq!(palette.header, "\x1b[34m");
+        assert_eq!(palette.holiday, "\x1b[1;35m");
+        assert_eq!(palette.workday, "\x1b[37m");
+
+        palette.apply_cal_colors("selected=green:marked=bold:othertoday=reverse");
+        assert_eq!(palette.selected, "\x1b[32m");
+        assert_eq!(palette.marked, "\x1b[1m");
+        assert_eq!(palette.othertoday, "\x1b[7m");
+    }
+
+    #[test]
+    fn most_specific_scheme_wins() {
+        let dir = std::env::temp_dir().join(format!("cal-colors-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let touch = |name: &str| std::fs::write(dir.join(name), "").unwrap();
+
+        // No bare or terminal-only scheme: schemes carry the cal name
+        touch("scheme");
+        touch("@xterm.scheme");
+        touch("ls.scheme");
+        assert_eq!(TerminalColors::find(&dir, Some("xterm")).scheme, None);
+
+        touch("cal.scheme");
+        assert_eq!(
+            TerminalColors::find(&dir, Some("xterm")).scheme,
+            Some(dir.join("cal.scheme"))
+        );
+        touch("cal@xterm.scheme");
+        assert_eq!(
+            TerminalColors::find(&dir, Some("xterm")).scheme,
+            Some(dir.join("cal@xterm.scheme"))
+        );
+        assert_eq!(
+            TerminalColors::find(&dir, Some("vt100")).scheme,
+            Some(dir.join("cal.scheme"))
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn enable_and_disable_files() {
+        let dir = std::env::temp_dir().join(format!("cal-enable-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let touch = |name: &str| std::fs::write(dir.join(name), "").unwrap();
+        let enabled = |term| TerminalColors::find(&dir, term).enabled;
+
+        assert_eq!(enabled(None), None);
+        touch("disable");
+        assert_eq!(enabled(None), Some(false));
+        // A more specific enable overrides the generic disable
+        touch("@xterm.enable");
+        assert_eq!(enabled(Some("xterm")), Some(true));
+        assert_eq!(enabled(Some("vt100")), Some(false));
+        // The utility name is more specific than the terminal
+        touch("cal.disable");
+        assert_eq!(enabled(Some("xterm")), Some(false));
+        touch("cal.enable");
+        assert_eq!(enabled(Some("xterm")), Some(false));
+        touch("cal@xterm.enable");
+        assert_eq!(enabled(Some("xterm")), Some(true));
+        // Other utilities' files are ignored
+        touch("dmesg@vt100.enable");
+        assert_eq!(enabled(Some("vt100")), Some(false));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn palette_colors_days() {
+        let mut ctx = base_context();
+        ctx.color = true;
+        ctx.palette.weekend = "\x1b[32m".to_string();
+        ctx.palette.today = "\x1b[1m".to_string();
+        let m = MonthData::new(&ctx, 2026, 2);
+        let body = format_month_grid(&ctx, &m)[2..].join("\n");
+        assert!(body.contains("\x1b[32m 1"));
+        assert!(body.contains("\x1b[1m18"));
+    }
+
+    #[test]
+    fn empty_sequence_means_no_color() {
+        let mut ctx = base_context();
+        ctx.color = true;
+        ctx.palette.weekend = String::new();
+        ctx.palette.header = String::new();
+        let m = MonthData::new(&ctx, 2026, 3);
+        assert!(!format_month_grid(&ctx, &m)[0].contains('\x1b'));
+        assert!(
+            !format_month_grid(&ctx, &m)[2..]
+                .join("")
+                .contains("\x1b[91m")
+        );
+    }
+}
+
+// ============================================================================
+// Color themes
+// ============================================================================
+
+mod themes {
+    use super::*;
+    use cal::colors::{ColorDepth, is_light, parse_colorfgbg, parse_osc11_reply};
+    use cal::query::Query;
+    use cal::types::Theme;
+
+    #[test]
+    fn color_depth_detection() {
+        assert_eq!(
+            ColorDepth::detect(Some("truecolor"), Some("xterm")),
+            ColorDepth::TrueColor
+        );
+        assert_eq!(
+            ColorDepth::detect(Some("24bit"), None),
+            ColorDepth::TrueColor
+        );
+        assert_eq!(
+            ColorDepth::detect(None, Some("xterm-256color")),
+            ColorDepth::Ansi256
+        );
+        assert_eq!(ColorDepth::detect(None, Some("linux")), ColorDepth::Basic);
+        assert_eq!(ColorDepth::detect(None, None), ColorDepth::Basic);
+    }
+
+    #[test]
+    fn theme_sequences_follow_depth() {
+        let basic = Palette::theme(Theme::SolarizedLight, ColorDepth::Basic);
+        let indexed = Palette::theme(Theme::SolarizedLight, ColorDepth::Ansi256);
+        let rgb = Palette::theme(Theme::SolarizedLight, ColorDepth::TrueColor);
+        assert_eq!(basic.header, "\x1b[34m");
+        assert_eq!(indexed.header, "\x1b[38;5;33m");
+        assert_eq!(rgb.header, "\x1b[38;2;38;139;210m");
+    }
+
+    #[test]
+    fn light_theme_avoids_bright_yellow() {
+        let palette = Palette::theme(Theme::SolarizedLight, ColorDepth::Basic);
+        assert_ne!(palette.weekdays, Palette::default().weekdays);
+        assert_eq!(
+            Palette::theme(Theme::Default, ColorDepth::TrueColor),
+            Palette::default()
+        );
+    }
+
+    #[test]
+    fn mono_theme_has_no_colors() {
+        let palette = Palette::theme(Theme::Mono, ColorDepth::TrueColor);
+        for sequence in [
+            &palette.today,
+            &palette.header,
+            &palette.weekdays,
+            &palette.weekend,
+            &palette.holiday,
+            &palette.shortened,
+            &palette.selected,
+            &palette.marked,
+            &palette.othertoday,
+        ] {
+            assert!(!sequence.contains("38;") && !sequence.contains("[3"));
+        }
+    }
+
+    #[test]
+    fn day_highlights_come_from_the_palette() {
+        let mut ctx = base_context();
+        ctx.color = true;
+        ctx.palette = Palette::theme(Theme::Mono, ColorDepth::Basic);
+        ctx.selected = Some((2026, 2, 5));
+        ctx.find = Some(Query::parse("fri 13").unwrap());
+        let m = MonthData::new(&ctx, 2026, 2);
+        let body = format_month_grid(&ctx, &m)[2..].join("\n");
+        assert!(body.contains(&format!("{} 5", ctx.palette.selected)));
+        assert!(body.contains(&format!("{}13", ctx.palette.marked)));
+        assert!(!body.contains("\x1b[30;103m") && !body.contains("\x1b[95m"));
+    }
+
+    #[test]
+    fn colorblind_theme_avoids_red() {
+        let palette = Palette::theme(Theme::Colorblind, ColorDepth::Basic);
+        assert!(!palette.weekend.contains("31m") && !palette.weekend.contains("91m"));
+        assert!(!palette.holiday.contains("31m") && !palette.holiday.contains("91m"));
+        assert_ne!(palette.weekend, palette.holiday);
+    }
+
+    #[test]
+    fn osc11_reply() {
+        assert_eq!(
+            parse_osc11_reply("\x1b]11;rgb:ffff/ffff/ffff\x07"),
+            Some((1.0, 1.0, 1.0))
+        );
+        assert_eq!(
+            parse_osc11_reply("\x1b]11;rgb:00/00/00\x1b\\"),
+            Some((0.0, 0.0, 0.0))
+        );
+        assert!(is_light(
+            parse_osc11_reply("\x1b]11;rgb:fdfd/f6f6/e3e3\x07").unwrap()
+        ));
+        assert!(!is_light(
+            parse_osc11_reply("\x1b]11;rgb:0000/2b2b/3636\x07").unwrap()
+        ));
+        assert_eq!(parse_osc11_reply(""), None);
+        assert_eq!(parse_osc11_reply("\x1b]11;rgb:ffff/ffff\x07"), None);
+    }
+
+    #[test]
+    fn colorfgbg_variable() {
+        assert_eq!(parse_colorfgbg("0;15"), Some(true));
+        assert_eq!(parse_colorfgbg("15;default;0"), Some(false));
+        assert_eq!(parse_colorfgbg("12;8"), Some(false));
+        assert_eq!(parse_colorfgbg("garbage"), None);
+    }
+
+    #[test]
+    fn theme_option() {
+        let args = Args::try_parse_from(["cal", "--theme", "solarized-light"]).unwrap();
+        assert_eq!(args.theme, Some(Theme::SolarizedLight));
+        assert_eq!(Args::try_parse_from(["cal"]).unwrap().theme, None);
+        let args = Args::try_parse_from(["cal", "--theme", "auto"]).unwrap();
+        assert_eq!(args.theme, Some(Theme::Auto));
+        assert!(Args::try_parse_from(["cal", "--theme", "neon"]).is_err());
+    }
+}
+
+// ============================================================================
+// Config file and profiles
+// ============================================================================
+
+mod config_file {
+    use super::*;
+    use cal::config::Config;
+    use clap::{CommandFactory, FromArgMatches};
+
+    const CONFIG: &str = r#"
+week-start = "sunday"
+reform = "iso"
+columns = 2
+week_numbers = true
+
+[profiles.work]
+three = true
+tz = ["Europe/Berlin", "America/New_York"]
+week-type = "us"
+"#;
+
+    /// Parse a command line over the config like Args::parse does.
+    fn parse_with(config: &mut Config, cli: &[&str]) -> Args {
+        let cmd = Args::command();
+        let matches = cmd.clone().try_get_matches_from(cli).unwrap();
+        let cmd = config.apply(cmd, &matches).unwrap();
+        Args::from_arg_matches(&cmd.try_get_matches_from(cli).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn top_level_settings() {
+        let mut config = Config::parse(CONFIG, None).unwrap();
+        let args = parse_with(&mut config, &["cal"]);
+        assert!(args.sunday && args.week_numbers.is_some());
+        assert!(matches!(args.reform, ReformType::Iso));
+        assert_eq!(args.columns.as_deref(), Some("2"));
+        assert!(!args.three_months);
+        assert!(args.time_zones.is_empty());
+    }
+
+    #[test]
+    fn profile_overrides_top_level() {
+        let mut config = Config::parse(CONFIG, Some("work")).unwrap();
+        let args = parse_with(&mut config, &["cal"]);
+        assert!(args.three_months && args.sunday);
+        assert!(matches!(args.week_type, Some(WeekType::Us)));
+        assert_eq!(args.time_zones, ["Europe/Berlin", "America/New_York"]);
+    }
+
+    #[test]
+    fn command_line_wins() {
+        let mut config = Config::parse(CONFIG, None).unwrap();
+        let args = parse_with(&mut config, &["cal", "-m", "--iso", "-c", "auto", "2026"]);
+        assert!(args.monday && !args.sunday);
+        assert!(args.iso);
+        assert!(matches!(args.reform, ReformType::Year1752));
+        assert_eq!(args.columns.as_deref(), Some("auto"));
+        assert_eq!(args.day_arg.as_deref(), Some("2026"));
+        assert_eq!(config.applied, ["week-numbers"]);
+    }
+
+    #[test]
+    fn config_values_stay_defaults() {
+        let mut config = Config::parse("color = \"never\"\nmonths = 4", None).unwrap();
+        let cmd = Args::command();
+        let matches = cmd.clone().try_get_matches_from(["cal"]).unwrap();
+        let matches = config
+            .apply(cmd, &matches)
+            .unwrap()
+            .try_get_matches_from(["cal", "-y"])
+            .unwrap();
+        // The config does not count as command-line input
+        assert_eq!(
+            matches.value_source("months_count"),
+            Some(clap::parser::ValueSource::DefaultValue)
+        );
+        assert_eq!(
+            matches.value_source("color"),
+            Some(clap::parser::ValueSource::DefaultValue)
+        );
+
+        // A display mode on the command line replaces the configured one
+        let mut config = Config::parse("months = 4", None).unwrap();
+        let args = parse_with(&mut config, &["cal", "-y"]);
+        assert!(args.year && args.months_count.is_none());
+        assert!(CalContext::new(&args).is_ok());
+    }
+
+    #[test]
+    fn unknown_profile() {
+        let err = Config::parse(CONFIG, Some("home")).unwrap_err();
+        assert!(err.contains("Unknown profile: home") && err.contains("work"));
+    }
+
+    #[test]
+    fn invalid_settings() {
+        let cmd = Args::command();
+        let cli = cmd.clone().try_get_matches_from(["cal"]).unwrap();
+        for text in [
+            "bogus = 1",
+            "columns = true",
+            "julian = \"yes\"",
+            "week-start = \"friday\"",
+            "profile = \"work\"",
+        ] {
+            let mut config = Config::parse(text, None).unwrap();
+            assert!(config.apply(cmd.clone(), &cli).is_err(), "{}", text);
+        }
+        assert!(Config::parse("columns = ", None).is_err());
+        assert!(Config::parse("profiles = 1", None).is_err());
+    }
+
+    #[test]
+    fn effective_settings_sources() {
+        let mut config = Config::parse(CONFIG, None).unwrap();
+        let cmd = Args::command();
+        let cli = ["cal", "-j"];
+        let matches = cmd.clone().try_get_matches_from(cli).unwrap();
+        let cmd = config.apply(cmd, &matches).unwrap();
+        let matches = cmd.clone().try_get_matches_from(cli).unwrap();
+
+        let effective = config.effective(&cmd, &matches);
+        let find = |key: &str| {
+            effective
+                .iter()
+                .find(|(name, _, _)| name == key)
+                .map(|(_, value, source)| (value.to_string(), *source))
+        };
+        assert_eq!(find("sunday"), Some(("true".to_string(), "config")));
+        assert_eq!(
+            find("week-numbers"),
+            Some(("\"current\"".to_string(), "config"))
+        );
+        assert_eq!(find("columns"), Some(("2".to_string(), "config")));
+        assert_eq!(find("julian"), Some(("true".to_string(), "command line")));
+        assert_eq!(find("theme"), None);
+        assert_eq!(find("week-type"), None);
+        assert_eq!(find("location"), None);
+        assert_eq!(find("print-config"), None);
+    }
+}
+
+// ============================================================================
+// Project calendar (.calendar.toml)
+// ============================================================================
+
+mod project_calendar {
+    use super::*;
+    use cal::formatter::footer_notes;
+    use cal::project::{EventKind, ProjectCalendar, discover};
+    use chrono::NaiveDate;
+
+    const PROJECT: &str = r#"
+[[holidays]]
+date = 2026-10-23
+name = "Team day off"
+
+[[releases]]
+date = "2026-10-29"
+name = "Release 2.0"
+
+[[freezes]]
+start = 2026-10-26
+end = 2026-10-29
+
+[sprints]
+start = 2026-01-05
+weeks = 2
+"#;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn project() -> ProjectCalendar {
+        ProjectCalendar::parse(PROJECT, ".calendar.toml".into()).unwrap()
+    }
+
+    #[test]
+    fn discovered_in_parent_directory() {
+        let base = std::env::temp_dir().join(format!("cal-project-{}", std::process::id()));
+        let deep = base.join("src").join("module");
+        std::fs::create_dir_all(&deep).unwrap();
+        std::fs::write(base.join(".calendar.toml"), "").unwrap();
+
+        assert_eq!(discover(&deep), Some(base.join(".calendar.toml")));
+        std::fs::write(deep.join(".calendar.toml"), "").unwrap();
+        assert_eq!(discover(&deep), Some(deep.join(".calendar.toml")));
+
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn events_and_priority() {
+        let project = project();
+        assert_eq!(project.events.len(), 3);
+        assert_eq!(project.events[1].name, "Code freeze");
+        assert_eq!(
+            project.event_on(date(2026, 10, 23)),
+            Some(EventKind::Holiday)
+        );
+        assert_eq!(
+            project.event_on(date(2026, 10, 27)),
+            Some(EventKind::Freeze)
+        );
+        assert_eq!(
+            project.event_on(date(2026, 10, 29)),
+            Some(EventKind::Release)
+        );
+        assert_eq!(project.event_on(date(2026, 10, 30)), None);
+        assert_eq!(
+            project
+                .events_between(date(2026, 10, 27), date(2026, 10, 27))
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn sprint_numbers() {
+        let project = project();
+        assert_eq!(
+            project.sprints_between(date(2026, 10, 12), date(2026, 10, 18)),
+            [(21, date(2026, 10, 12), date(2026, 10, 25))]
+        );
+        assert_eq!(
+            project
+                .sprints_between(date(2026, 10, 1), date(2026, 10, 31))
+                .len(),
+            3
+        );
+        assert_eq!(
+            project.sprints_between(date(2025, 12, 1), date(2026, 1, 10)),
+            [(1, date(2026, 1, 5), date(2026, 1, 18))]
+        );
+    }
+
+    #[test]
+    fn invalid_files() {
+        for text in [
+            "holidays = 1",
+            "[[releases]]\nname = \"no date\"",
+            "[[freezes]]\nstart = 2026-10-29\nend = 2026-10-26",
+            "[[holidays]]\ndate = \"29.10.2026\"",
+            "[sprints]\nweeks = 2",
+            "[sprints]\nstart = 2026-01-05\nlength = 0",
+        ] {
+            assert!(
+                ProjectCalendar::parse(text, ".calendar.toml".into()).is_err(),
+                "{}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn overlay_in_month_view() {
+        let mut ctx = base_context();
+        ctx.color = true;
+        ctx.project = project().into();
+        let m = MonthData::new(&ctx, 2026, 10);
+        let body = format_month_grid(&ctx, &m)[2..].join("\n");
+        assert!(body.contains(&format!("{}29", ctx.palette.release)));
+        assert!(body.contains(&format!("{}27", ctx.palette.freeze)));
+        assert!(body.contains(&format!("{}23", ctx.palette.holiday)));
+
+        let notes = footer_notes(&ctx, &[m]);
+        assert!(notes.contains(&"Release 2.0        2026-10-29".to_string()));
+        assert!(notes.contains(&"Code freeze        2026-10-26 .. 2026-10-29".to_string()));
+        assert!(notes.contains(&"Sprint 21          2026-10-12 .. 2026-10-25".to_string()));
+    }
+
+    #[test]
+    fn no_project_option() {
+        let args = Args::try_parse_from(["cal", "--no-project"]).unwrap();
+        assert!(CalContext::new(&args).unwrap().project.get().is_none());
+    }
+
+    #[test]
+    fn broken_file_is_read_on_use() {
+        let base = std::env::temp_dir().join(format!("cal-broken-{}", std::process::id()));
+        std::fs::create_dir_all(&base).unwrap();
+        std::fs::write(base.join(".calendar.toml"), "holidays = 1").unwrap();
+
+        let project = LazyProject::discover_in(Some(base.clone()));
+        assert_eq!(project.error(), None);
+        assert!(project.get().is_none());
+        assert!(
+            project
+                .error()
+                .unwrap()
+                .contains("'holidays' must be an array")
+        );
+
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+}
+
+// ============================================================================
+// Week start from the locale
+// ============================================================================
+
+mod locale_week_start {
+    use super::*;
+    use cal::formatter::{locale_week_for, week_defaults_in};
+
+    #[test]
+    fn first_weekday_of_locales() {
+        assert_eq!(
+            week_defaults_in(Locale::en_US),
+            (Weekday::Sun, WeekType::Us)
+        );
+        assert_eq!(
+            week_defaults_in(Locale::ru_RU),
+            (Weekday::Mon, WeekType::Iso)
+        );
+        assert_eq!(
+            week_defaults_in(Locale::de_DE),
+            (Weekday::Mon, WeekType::Iso)
+        );
+        assert_eq!(
+            week_defaults_in(Locale::en_GB),
+            (Weekday::Mon, WeekType::Iso)
+        );
+        assert_eq!(week_defaults_in(Locale::ar_EG).0, Weekday::Sat);
+    }
+
+    #[test]
+    fn locale_names() {
+        assert_eq!(
+            locale_week_for("en_US.UTF-8"),
+            Some((Weekday::Sun, WeekType::Us))
+        );
+        assert_eq!(
+            locale_week_for("ru_RU.UTF-8"),
+            Some((Weekday::Mon, WeekType::Iso))
+        );
+        assert_eq!(
+            locale_week_for("de_DE@euro"),
+            Some((Weekday::Mon, WeekType::Iso))
+        );
+        assert_eq!(locale_week_for("C"), None);
+        assert_eq!(locale_week_for("POSIX"), None);
+        assert_eq!(locale_week_for("C.UTF-8"), None);
+    }
+
+    #[test]
+    fn saturday_first_grid() {
+        let mut ctx = base_context();
+        ctx.week_start = Weekday::Sat;
+        assert_eq!(
+            get_weekday_order(Weekday::Sat),
+            [
+                Weekday::Sat,
+                Weekday::Sun,
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri
+            ]
+        );
+        // October 1, 2026 is a Thursday: five empty cells from Saturday
+        let m = MonthData::new(&ctx, 2026, 10);
+        assert_eq!(m.days[..6], [None, None, None, None, None, Some(1)]);
+    }
+
+    #[test]
+    fn flags_override_locale() {
+        let ctx = CalContext::new(&Args::try_parse_from(["cal", "-s"]).unwrap()).unwrap();
+        assert_eq!(ctx.week_start, Weekday::Sun);
+        let ctx = CalContext::new(&Args::try_parse_from(["cal", "-m"]).unwrap()).unwrap();
+        assert_eq!(ctx.week_start, Weekday::Mon);
+        assert!(matches!(ctx.week_type, WeekType::Iso));
+        let args = Args::try_parse_from(["cal", "-m", "--week-type", "us"]).unwrap();
+        assert!(matches!(
+            CalContext::new(&args).unwrap().week_type,
+            WeekType::Us
+        ));
+    }
+}
+
+// ===========================================================================
+// util-linux compatible layout
+// ===========================================================================
+
+mod compat_layout {
+    use super::*;
+    use cal::compat::{format_months, format_year, span_start};
+
+    fn compat_context() -> CalContext {
+        CalContext {
+            week_start: Weekday::Sun,
+            compat: true,
+            columns: ColumnsMode::Fixed(3),
+            ..base_context()
+        }
+    }
+
+    #[test]
+    fn september_1752_without_skipped_days() {
+        let lines = format_months(&compat_context(), 1752, 9, 1, false);
+        assert_eq!(
+            lines[2..],
+            [
+                "       1  2 14 15 16",
+                "17 18 19 20 21 22 23",
+                "24 25 26 27 28 29 30",
+                "                    ",
+                "                    ",
+                "                    ",
+            ]
+        );
+    }
+
+    #[test]
+    fn julian_days_skip_the_gap_numbers() {
+        let ctx = CalContext {
+            julian: true,
+            ..compat_context()
+        };
+        let lines = format_months(&ctx, 1752, 9, 1, false);
+        assert_eq!(lines[2], "        245 246 258 259 260");
+        assert_eq!(lines[4], "268 269 270 271 272 273 274");
+        assert!(lines.iter().all(|line| line.width() == 27));
+    }
+
+    #[test]
+    fn six_week_lines_with_trailing_spaces() {
+        let lines = format_months(&compat_context(), 2026, 2, 1, false);
+        assert_eq!(lines.len(), 8);
+        assert_eq!(lines[2], " 1  2  3  4  5  6  7");
+        assert_eq!(lines[6], "                    ");
+        assert!(lines.iter().all(|line| line.width() == 20));
+    }
+
+    #[test]
+    fn week_numbers_precede_each_line() {
+        let ctx = CalContext {
+            week_start: Weekday::Mon,
+            week_numbers: true,
+            ..compat_context()
+        };
+        let lines = format_months(&ctx, 2026, 2, 1, false);
+        assert_eq!(lines[2], " 5                    1");
+        assert_eq!(lines[3], " 6  2  3  4  5  6  7  8");
+        assert_eq!(lines[7], "                       ");
+    }
+
+    #[test]
+    fn three_months_use_a_two_column_gutter() {
+        let (year, month) = span_start(2026, 1, 3);
+        assert_eq!((year, month), (2025, 12));
+        let lines = format_months(&compat_context(), year, month, 3, false);
+        assert_eq!(lines.len(), 8);
+        assert!(lines.iter().all(|line| line.width() == 64));
+        assert_eq!(
+            lines[2],
+            "    1  2  3  4  5  6               1  2  3   1  2  3  4  5  6  7"
+        );
+    }
+
+    #[test]
+    fn year_header_is_centered_over_the_rows() {
+        let lines = format_year(&compat_context(), 2026);
+        assert_eq!(
+            lines[0],
+            format!("{}2026{}", " ".repeat(31), " ".repeat(31))
+        );
+        assert_eq!(lines[1], "");
+        // Four rows of a header, weekday names and six week lines
+        assert_eq!(lines.len(), 2 + 4 * 8);
+        assert!(lines[2..].iter().all(|line| line.width() == 66));
+    }
+
+    #[test]
+    fn vertical_months_have_fixed_columns() {
+        let ctx = CalContext {
+            vertical: true,
+            ..compat_context()
+        };
+        let lines = format_months(&ctx, 2026, 2, 1, false);
+        assert_eq!(lines.len(), 8);
+        let days: String = lines[1].chars().skip(2).collect();
+        assert_eq!(days, "  1  8 15 22      ");
+        assert!(lines[1..].iter().all(|line| line.width() == 20));
+    }
+
+    #[test]
+    fn highlighted_week_line() {
+        let ctx = CalContext {
+            week_numbers: true,
+            highlight_week: Some((2026, 8)),
+            color: true,
+            today: chrono::NaiveDate::from_ymd_opt(2000, 1, 1).unwrap(),
+            ..compat_context()
+        };
+        let week = ctx.palette.weekhighlight.clone();
+        let lines = format_months(&ctx, 2026, 2, 1, false);
+        // Sunday start, ISO numbers: the line of February 22-28 is numbered by the 22nd
+        let highlighted: Vec<&String> = lines.iter().filter(|l| l.contains(&week)).collect();
+        assert_eq!(highlighted.len(), 1);
+        assert!(highlighted[0].contains("22"));
+        assert_eq!(highlighted[0].matches(week.as_str()).count(), 8);
+    }
+}
+
+// ===========================================================================
+// BSD ncal personality
+// ===========================================================================
+
+mod ncal_personality {
+    use super::*;
+    use cal::ncal::{is_ncal, reform_table, translate};
+    use clap::CommandFactory;
+    use std::ffi::OsString;
+
+    fn ncal(args: &[&str]) -> Vec<String> {
+        let argv: Vec<OsString> = args.iter().map(OsString::from).collect();
+        translate(argv, &Args::command())
+            .unwrap()
+            .iter()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn detected_by_program_name_or_flag() {
+        assert!(is_ncal(&["/usr/bin/ncal".into()]));
+        assert!(is_ncal(&["cal".into(), "--ncal".into()]));
+        assert!(!is_ncal(&["cal".into(), "--".into(), "--ncal".into()]));
+        assert_eq!(ncal(&["cal", "-s"]), ["cal", "-s"]);
+    }
+
+    #[test]
+    fn options_are_translated() {
+        assert_eq!(
+            ncal(&["ncal", "-wS", "2026"]),
+            ["ncal", "--ncal", "--vertical", "-w", "--sunday", "2026"]
+        );
+        assert_eq!(
+            ncal(&["ncal", "-bJM"]),
+            ["ncal", "--ncal", "--reform=julian", "--monday"]
+        );
+        assert_eq!(
+            ncal(&["ncal", "-o", "-sgb"]),
+            [
+                "ncal",
+                "--ncal",
+                "--vertical",
+                "--easter=orthodox",
+                "--reform=1752"
+            ]
+        );
+        assert_eq!(
+            ncal(&["cal", "--ncal", "-n3", "--tz", "UTC"]),
+            ["cal", "--ncal", "--vertical", "-n", "3", "--tz", "UTC"]
+        );
+    }
+
+    #[test]
+    fn month_option_becomes_positional() {
+        assert_eq!(
+            ncal(&["ncal", "2026", "-m", "feb"]),
+            ["ncal", "--ncal", "--vertical", "feb", "2026"]
+        );
+        let args = Args::parse_from(ncal(&["ncal", "-m3", "2026"]));
+        assert!(args.vertical && args.ncal);
+        assert_eq!(
+            get_display_date(&args, &context_for(&args)).unwrap(),
+            (2026, 3, None)
+        );
+    }
+
+    #[test]
+    fn unsupported_country_is_an_error() {
+        let argv: Vec<OsString> = ["ncal", "-s", "RU"].iter().map(OsString::from).collect();
+        let err = translate(argv, &Args::command()).unwrap_err();
+        assert!(err.contains("Russia (1918-01-31)"));
+        let argv: Vec<OsString> = ["ncal", "-s", "XX"].iter().map(OsString::from).collect();
+        assert!(translate(argv, &Args::command()).is_err());
+    }
+
+    #[test]
+    fn reform_table_marks_current_reform() {
+        let lines = reform_table(&base_context());
+        assert_eq!(lines.len(), 18);
+        assert_eq!(
+            lines[0],
+            " AL Albania        1912-11-30      IT Italy          1582-10-04"
+        );
+        assert!(lines[14].starts_with("*GB United Kingdom 1752-09-02"));
+        assert_eq!(lines[17], " IS Iceland        1700-11-16");
+
+        let ctx = CalContext {
+            reform_year: ReformType::Julian.reform_year(),
+            ..base_context()
+        };
+        let table = reform_table(&ctx);
+        let marked: Vec<&String> = table.iter().filter(|line| line.contains('*')).collect();
+        assert_eq!(marked.len(), 1);
+        assert!(marked[0].contains("*LN Latin"));
+    }
+
+    #[test]
+    fn orthodox_easter() {
+        let ctx = base_context();
+        assert_eq!(ctx.orthodox_easter(2026), (4, 12));
+        assert_eq!(ctx.orthodox_easter(2024), (5, 5));
+        // Same Sunday as the western Easter
+        assert_eq!(ctx.orthodox_easter(2025), ctx.easter(2025));
+        let julian = CalContext {
+            reform_year: ReformType::Julian.reform_year(),
+            ..base_context()
+        };
+        assert_eq!(julian.orthodox_easter(2026), (3, 30));
+    }
+}
+
+// ===========================================================================
+// Clock
+// ===========================================================================
+
+mod clock {
+    use super::*;
+    use cal::clock::Clock;
+    use cal::zoneinfo::TimeZone;
+
+    #[test]
+    fn epoch_seconds_as_in_util_linux() {
+        let clock = Clock::parse("1771416000", None).unwrap();
+        assert_eq!(clock.now().to_rfc3339(), "2026-02-18T12:00:00+00:00");
+        let tokyo = TimeZone::load("<+09>-9").unwrap();
+        let honolulu = TimeZone::load("<-10>10").unwrap();
+        let late = Clock::parse("1771455600", None).unwrap(); // 23:00 UTC
+        assert_eq!(late.today(Some(&tokyo)).day(), 19);
+        assert_eq!(late.today(Some(&honolulu)).day(), 18);
+        assert!(Clock::parse("tomorrow", None).is_none());
+    }
+
+    #[test]
+    fn date_holds_in_the_reference_zone() {
+        let date = chrono::NaiveDate::from_ymd_opt(2026, 2, 18).unwrap();
+        for name in ["<+14>-14", "<-12>12", "UTC"] {
+            let zone = TimeZone::load(name).unwrap();
+            let clock = Clock::parse("2026-02-18", Some(&zone)).unwrap();
+            assert_eq!(clock.today(Some(&zone)), date, "{name}");
+        }
+        assert_eq!(Clock::at_date(date, None).today(None), date);
+    }
+
+    #[test]
+    fn offset_shifts_the_system_clock() {
+        let clock = Clock::Offset(chrono::TimeDelta::days(1));
+        let ahead = clock.now() - Clock::System.now() - chrono::TimeDelta::days(1);
+        assert!(ahead.num_seconds().abs() < 5);
+    }
+
+    #[test]
+    fn today_option_fixes_relative_dates() {
+        let args = Args::parse_from(["cal", "--today", "2026-12-15", "next"]);
+        assert_eq!(
+            get_display_date(&args, &context_for(&args)).unwrap(),
+            (2027, 1, None)
+        );
+        let ctx = CalContext::new(&args).unwrap();
+        assert_eq!(
+            ctx.today,
+            chrono::NaiveDate::from_ymd_opt(2026, 12, 15).unwrap()
+        );
+        assert!(Args::try_parse_from(["cal", "--today", "15.12.2026"]).is_err());
+    }
+
+    #[test]
+    fn context_clock_drives_relative_dates() {
+        let args = Args::parse_from(["cal", "next", "month"]);
+        let date = chrono::NaiveDate::from_ymd_opt(2026, 12, 15).unwrap();
+        let ctx = CalContext::with_clock(&args, Clock::at_date(date, None)).unwrap();
+        assert_eq!(get_display_date(&args, &ctx).unwrap(), (2027, 1, None));
+    }
+
+    #[test]
+    fn context_with_clock() {
+        let args = Args::parse_from(["cal", "--tz", "<+14>-14", "--tz", "<-12>12"]);
+        let clock = Clock::parse("1771416000", None).unwrap();
+        let ctx = CalContext::with_clock(&args, clock).unwrap();
+        assert_eq!(
+            ctx.today,
+            chrono::NaiveDate::from_ymd_opt(2026, 2, 19).unwrap()
+        );
+        assert_eq!(
+            ctx.zone_todays,
+            [(
+                "<-12>12".to_string(),
+                chrono::NaiveDate::from_ymd_opt(2026, 2, 18).unwrap()
+            )]
+        );
+    }
+}
+
+// ===========================================================================
+// Rendering into any writer
+// ===========================================================================
+
+mod rendering {
+    use super::*;
+    use cal::error::{CalError, ErrorKind};
+    use cal::formatter::{
+        print_month, print_month_vertical, print_period, print_three_months, print_year,
+    };
+    use std::io::{self, Write};
+
+    /// Writer whose reader has gone away.
+    struct ClosedPipe;
+
+    impl Write for ClosedPipe {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::ErrorKind::BrokenPipe.into())
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn month_into_buffer() {
+        let ctx = base_context();
+        let mut out = Vec::new();
+        print_month(&mut out, &ctx, 2026, 2).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let grid = format_month_grid(&ctx, &MonthData::new(&ctx, 2026, 2));
+        assert_eq!(text, grid.join("\n") + "\n");
+    }
+
+    #[test]
+    fn vertical_year_into_buffer() {
+        let ctx = CalContext {
+            vertical: true,
+            ..base_context()
+        };
+        let mut out = Vec::new();
+        print_year(&mut out, &ctx, 2026).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(text.lines().next().unwrap().trim(), "2026");
+        // Title, blank line, then four rows of a header, seven weekdays and a blank line
+        assert_eq!(text.lines().count(), 2 + 4 * 9);
+    }
+
+    #[test]
+    fn vertical_rows_follow_week_start() {
+        let ctx = CalContext {
+            vertical: true,
+            week_start: Weekday::Sun,
+            ..base_context()
+        };
+        let mut out = Vec::new();
+        print_month_vertical(&mut out, &ctx, &MonthData::new(&ctx, 2026, 2), true).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let days = |line: &str| {
+            line.split_whitespace()
+                .skip(1)
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+        let lines: Vec<&str> = text.lines().collect();
+        // February 2026 starts on a Sunday, the first row
+        assert_eq!(days(lines[1]), ["1", "8", "15", "22"]);
+        assert_eq!(days(lines[7]), ["7", "14", "21", "28"]);
+    }
+
+    #[test]
+    fn vertical_quarter_side_by_side() {
+        let ctx = CalContext {
+            vertical: true,
+            gutter_width: 1,
+            ..base_context()
+        };
+        let mut out = Vec::new();
+        print_period(&mut out, &ctx, 2026, Period::Quarter(1)).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0].trim(), "Q1 2026");
+        assert_eq!(lines[0].width(), ctx.row_width(3));
+        // Title, blank line, then one row of a header, seven weekdays and a blank line
+        assert_eq!(lines.len(), 2 + 9);
+        assert_eq!(lines[2].split_whitespace().count(), 3);
+    }
+
+    #[test]
+    fn period_title_spans_the_row() {
+        let ctx = CalContext {
+            week_numbers: true,
+            ..base_context()
+        };
+        let mut out = Vec::new();
+        print_period(&mut out, &ctx, 2026, Period::Half(1)).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let title = text.lines().next().unwrap();
+        assert_eq!(title.width(), 3 * 23 + 2 * 2);
+    }
+
+    #[test]
+    fn write_errors_are_propagated() {
+        let err = print_three_months(&mut ClosedPipe, &base_context(), 2026, 2).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+        let err = CalError::from(err);
+        assert_eq!(err.kind, ErrorKind::Output(io::ErrorKind::BrokenPipe));
+        assert_eq!(err.exit_code(), 1);
+    }
+}